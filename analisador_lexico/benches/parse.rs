@@ -0,0 +1,198 @@
+use std::{collections::HashMap, time::Instant};
+
+use analisador_lexico::{
+    class::Class,
+    embedded_tables,
+    grammar::Grammar,
+    parser::Parser,
+    scanner::Scanner,
+    slr_table::{ActionTable, GotoTable, SLRAction},
+    table_builder,
+};
+
+const STATEMENTS: usize = 50_000;
+
+fn generate() -> String {
+    let mut source = String::from(
+        "inicio\n    varinicio\n        inteiro A, B;\n        real C;\n    varfim;\n",
+    );
+
+    for i in 0..STATEMENTS {
+        match i % 4 {
+            0 => source.push_str("    A<-B+1;\n"),
+            1 => source.push_str("    se(A>B)\n    entao\n        escreva A;\n    fimse\n"),
+            2 => source
+                .push_str("    repita (B<5)\n        C<-B*2;\n        B<-B+1;\n    fimrepita\n"),
+            _ => source.push_str("    leia B;\n    escreva \"B=\";\n"),
+        }
+    }
+    source.push_str("fim\n");
+
+    source
+}
+
+/// The tables as they were before symbols were interned: maps keyed by the
+/// state and the name of the symbol, so each step allocates the name of the
+/// token's class and hashes it. Kept as the baseline the flat tables are
+/// measured against.
+struct StringKeyed {
+    actions: HashMap<(usize, String), SLRAction>,
+    gotos: HashMap<(usize, String), usize>,
+}
+
+impl StringKeyed {
+    fn new(grammar: &Grammar, action_table: &ActionTable, goto_table: &GotoTable) -> StringKeyed {
+        let mut actions = HashMap::new();
+        for state in 0..action_table.states() {
+            for (id, terminal) in grammar.terminals().into_iter().enumerate() {
+                actions.insert((state, terminal), action_table.get(state, id));
+            }
+        }
+
+        let mut gotos = HashMap::new();
+        for state in 0..goto_table.states() {
+            for (id, non_terminal) in grammar.non_terminals().into_iter().enumerate() {
+                if let Some(goto) = goto_table.try_get(state, id) {
+                    gotos.insert((state, non_terminal), goto);
+                }
+            }
+        }
+
+        StringKeyed { actions, gotos }
+    }
+}
+
+/// Shifts and reduces `classes` with the given lookups, without semantic
+/// actions, and returns the number of steps. Only the table accesses
+/// differ between the baseline and the flat tables.
+fn recognize<A, G>(grammar: &Grammar, classes: &[Class], action: A, goto: G) -> usize
+where
+    A: Fn(usize, &Class) -> SLRAction,
+    G: Fn(usize, usize) -> usize,
+{
+    let mut stack: Vec<usize> = vec![0];
+    let mut input = classes.iter();
+    let mut class = input.next().unwrap();
+    let mut steps = 0;
+
+    loop {
+        steps += 1;
+        match action(*stack.last().unwrap(), class) {
+            SLRAction::S(state) => {
+                stack.push(state);
+                class = input.next().unwrap();
+            }
+            SLRAction::R(rule) => {
+                let rule = &grammar.rules()[rule];
+                stack.truncate(stack.len() - rule.right.len());
+                stack.push(goto(*stack.last().unwrap(), rule.left.id));
+            }
+            SLRAction::Acc => return steps,
+            SLRAction::E(_) => panic!("erro sintático no programa gerado"),
+        }
+    }
+}
+
+fn lookups(table: &ActionTable) -> f64 {
+    let start = Instant::now();
+    let mut shifts = 0;
+    for _ in 0..1000 {
+        for state in 0..table.states() {
+            for terminal in 0..table.terminals() {
+                if let SLRAction::S(_) = table.get(state, terminal) {
+                    shifts += 1;
+                }
+            }
+        }
+    }
+    assert!(shifts > 0);
+
+    start.elapsed().as_secs_f64() * 1000.0
+}
+
+fn main() {
+    let source = generate();
+    let mut scanner = Scanner::from_str(&source);
+    let mut parser = Parser::new();
+
+    let start = Instant::now();
+    let program = parser.parse(&mut scanner);
+    let elapsed = start.elapsed();
+
+    assert!(program.is_some());
+    println!(
+        "parse de {} comandos: {:.2} ms",
+        STATEMENTS,
+        elapsed.as_secs_f64() * 1000.0
+    );
+
+    let grammar = Grammar::new();
+    let classes: Vec<Class> = Scanner::from_str(&source)
+        .map(|token| token.class.unwrap())
+        .collect();
+    let action_table = embedded_tables::action_table();
+    let goto_table = embedded_tables::goto_table();
+    let baseline = StringKeyed::new(&grammar, &action_table, &goto_table);
+    let non_terminals = grammar.non_terminals();
+
+    let start = Instant::now();
+    let steps = recognize(
+        &grammar,
+        &classes,
+        |state, class| baseline.actions[&(state, Class::to_str(Some(class.clone())))],
+        |state, non_terminal| baseline.gotos[&(state, non_terminals[non_terminal].clone())],
+    );
+    println!(
+        "reconhecimento com tabelas indexadas por nome ({} passos): {:.2} ms",
+        steps,
+        start.elapsed().as_secs_f64() * 1000.0
+    );
+
+    // As in the parser, the terminal id of each class is looked up once.
+    let mut terminal_ids: Vec<usize> = Vec::new();
+    for (id, terminal) in grammar.terminals().iter().enumerate() {
+        if let Some(class) = Class::from_lexeme(terminal) {
+            let index = class as usize;
+            if index >= terminal_ids.len() {
+                terminal_ids.resize(index + 1, 0);
+            }
+            terminal_ids[index] = id;
+        }
+    }
+
+    let start = Instant::now();
+    let flat_steps = recognize(
+        &grammar,
+        &classes,
+        |state, class| action_table.get(state, terminal_ids[class.clone() as usize]),
+        |state, non_terminal| goto_table.get(state, non_terminal),
+    );
+    assert_eq!(flat_steps, steps);
+    println!(
+        "reconhecimento com tabelas indexadas por id ({} passos): {:.2} ms",
+        flat_steps,
+        start.elapsed().as_secs_f64() * 1000.0
+    );
+
+    let mut tables = table_builder::build_slr(&grammar);
+    println!(
+        "tabela de ações densa ({} células): {:.2} ms",
+        tables.action_table.len(),
+        lookups(&tables.action_table)
+    );
+    let dense = table_builder::build_slr(&grammar).action_table;
+    tables.action_table.compress();
+    for state in 0..dense.states() {
+        for terminal in 0..dense.terminals() {
+            assert_eq!(
+                dense.get(state, terminal),
+                tables.action_table.get(state, terminal)
+            );
+        }
+    }
+    println!(
+        "tabela de ações compactada ({} posições): {:.2} ms",
+        tables.action_table.slots(),
+        lookups(&tables.action_table)
+    );
+}
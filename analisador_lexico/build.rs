@@ -0,0 +1,86 @@
+//! Builds the SLR(1) tables of the Mgol grammar and writes them as Rust data,
+//! so the parser doesn't have to construct them (or read them from disk) at
+//! run time. See src/embedded_tables.rs.
+
+#![allow(dead_code)]
+
+use std::{env, fmt::Write, fs, path::Path};
+
+#[path = "src/dot.rs"]
+mod dot;
+#[path = "src/first_follow.rs"]
+mod first_follow;
+#[path = "src/grammar.rs"]
+mod grammar;
+#[path = "src/interner.rs"]
+mod interner;
+#[path = "src/lr0.rs"]
+mod lr0;
+#[path = "src/lr1.rs"]
+mod lr1;
+#[path = "src/slr_table.rs"]
+mod slr_table;
+#[path = "src/table_builder.rs"]
+mod table_builder;
+
+use grammar::Grammar;
+
+fn main() {
+    for source in [
+        "src/mgol.bnf",
+        "src/first_follow.rs",
+        "src/grammar.rs",
+        "src/interner.rs",
+        "src/lr0.rs",
+        "src/lr1.rs",
+        "src/slr_table.rs",
+        "src/table_builder.rs",
+    ] {
+        println!("cargo:rerun-if-changed={}", source);
+    }
+
+    let grammar = Grammar::new();
+    let tables = table_builder::build_slr(&grammar);
+    let terminals = grammar.terminals().len();
+    let non_terminals = grammar.non_terminals().len();
+
+    let mut code = String::new();
+    writeln!(code, "pub const STATES: usize = {};", tables.states).unwrap();
+    writeln!(code, "pub const TERMINALS: usize = {};", terminals).unwrap();
+    writeln!(code, "pub const NON_TERMINALS: usize = {};", non_terminals).unwrap();
+
+    writeln!(
+        code,
+        "pub static ACTIONS: [SLRAction; {}] = [",
+        tables.states * terminals
+    )
+    .unwrap();
+    for state in 0..tables.states {
+        for terminal in 0..terminals {
+            writeln!(
+                code,
+                "    SLRAction::{:?},",
+                tables.action_table.get(state, terminal)
+            )
+            .unwrap();
+        }
+    }
+    writeln!(code, "];").unwrap();
+
+    writeln!(
+        code,
+        "pub static GOTOS: [Option<usize>; {}] = [",
+        tables.states * non_terminals
+    )
+    .unwrap();
+    for state in 0..tables.states {
+        for non_terminal in 0..non_terminals {
+            let goto = tables.goto_table.try_get(state, non_terminal);
+            writeln!(code, "    {:?},", goto).unwrap();
+        }
+    }
+    writeln!(code, "];").unwrap();
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+    fs::write(Path::new(&out_dir).join("mgol_tables.rs"), code).unwrap();
+}
@@ -0,0 +1,95 @@
+use crate::{span::Span, token::Token};
+
+#[derive(Clone, Debug)]
+pub struct Program {
+    pub declarations: Vec<Declaration>,
+    pub commands: Vec<Command>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Declaration {
+    pub data_type: Token,
+    pub ids: Vec<Token>,
+}
+
+#[derive(Clone, Debug)]
+pub enum Command {
+    Read {
+        keyword: Token,
+        id: Token,
+    },
+    Write {
+        keyword: Token,
+        argument: Argument,
+    },
+    Assign {
+        id: Token,
+        rcb: Token,
+        value: Expression,
+    },
+    If {
+        keyword: Token,
+        condition: Condition,
+        body: Vec<Command>,
+    },
+    Repeat {
+        keyword: Token,
+        condition: Condition,
+        body: Vec<Command>,
+    },
+}
+
+#[derive(Clone, Debug)]
+pub enum Argument {
+    Literal(Token),
+    Number(Token),
+    Id(Token),
+}
+
+#[derive(Clone, Debug)]
+pub enum Operand {
+    Id(Token),
+    Number(Token),
+}
+
+impl Operand {
+    pub fn token(&self) -> &Token {
+        match self {
+            Operand::Id(token) | Operand::Number(token) => token,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Expression {
+    Operand(Operand),
+    Arithmetic {
+        left: Operand,
+        operator: Token,
+        right: Operand,
+    },
+}
+
+impl Expression {
+    pub fn span(&self) -> Span {
+        match self {
+            Expression::Operand(operand) => operand.token().span,
+            Expression::Arithmetic { left, right, .. } => {
+                left.token().span.to(right.token().span)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Condition {
+    pub left: Operand,
+    pub operator: Token,
+    pub right: Operand,
+}
+
+impl Condition {
+    pub fn span(&self) -> Span {
+        self.left.token().span.to(self.right.token().span)
+    }
+}
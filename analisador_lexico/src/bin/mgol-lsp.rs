@@ -0,0 +1,17 @@
+use std::{io, process};
+
+use analisador_lexico::lsp;
+
+/// The Mgol language server, speaking the protocol over stdin and stdout.
+fn main() {
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+
+    match lsp::serve(stdin.lock(), stdout.lock()) {
+        Ok(status) => process::exit(status),
+        Err(err) => {
+            eprintln!("Erro: {}", err);
+            process::exit(1);
+        }
+    }
+}
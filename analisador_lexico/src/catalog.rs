@@ -0,0 +1,265 @@
+use std::env;
+
+use crate::diagnostic::{Code, Phase};
+
+/// The languages diagnostics can be shown in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    PtBr,
+    En,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 2] = [Locale::PtBr, Locale::En];
+
+    /// Accepts `pt-BR`, `pt_BR`, `pt`, `en`, `en-US` and the like, in any
+    /// case, optionally followed by an encoding as in `en_US.UTF-8`.
+    pub fn from_name(name: &str) -> Option<Locale> {
+        let name = name.split('.').next().unwrap_or("").to_lowercase().replace('_', "-");
+
+        match name.split('-').next() {
+            Some("pt") => Some(Locale::PtBr),
+            Some("en") => Some(Locale::En),
+            _ => None,
+        }
+    }
+
+    /// The locale named by the `MGOL_LANG` environment variable, if it is set
+    /// to one that is known.
+    pub fn from_env() -> Option<Locale> {
+        Locale::from_name(&env::var("MGOL_LANG").ok()?)
+    }
+
+    pub fn to_str(locale: Locale) -> String {
+        match locale {
+            Locale::PtBr => String::from("pt-BR"),
+            Locale::En => String::from("en"),
+        }
+    }
+}
+
+/// Every piece of text a diagnostic is made of. Templates refer to their
+/// arguments as `{0}`, `{1}`, ...
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Text {
+    /// The main message of each code.
+    Message(Code),
+
+    // Labels
+    InThisNumber,
+    FirstDeclaredHere,
+    DeclaredAs,
+    HasTypeLiteral,
+    ProgramEndsHere,
+    Skipped,
+
+    // Notes
+    DeclareBetween,
+    /// `{0}` the terminals the parser accepts, as they are written.
+    Expected,
+    TokenRemoved,
+    Inserted,
+    Replaced,
+    Unrecoverable,
+    Resumed,
+
+    // Fixes
+    RemoveCharacter,
+    CloseWith,
+    RemoveDeclaration,
+    Insert,
+    RemoveRepeated,
+    Remove,
+    Replace,
+
+    // Output
+    Error,
+    Warning,
+    Note,
+    Help,
+    /// `{0}` line, `{1}` column and `{2}` message.
+    ErrorAt(Phase),
+    NoErrors,
+    OneError,
+    ManyErrors,
+    OneErrorIn(Phase),
+    ManyErrorsIn(Phase),
+}
+
+/// The template of `text` in `locale`. Each catalog is an exhaustive
+/// `match` on `Text`, so a code without a message in some language does not
+/// compile.
+pub fn template(locale: Locale, text: Text) -> &'static str {
+    match locale {
+        Locale::PtBr => pt_br(text),
+        Locale::En => en(text),
+    }
+}
+
+/// `text` in `locale` with its placeholders replaced by `args`.
+pub fn format(locale: Locale, text: Text, args: &[String]) -> String {
+    let template = template(locale, text);
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(open) = rest.find('{') {
+        result.push_str(&rest[..open]);
+        rest = &rest[open..];
+
+        let argument = rest
+            .find('}')
+            .and_then(|close| Some((close, rest[1..close].parse::<usize>().ok()?)));
+        match argument {
+            Some((close, n)) => {
+                result.push_str(args.get(n).map(String::as_str).unwrap_or(""));
+                rest = &rest[close + 1..];
+            }
+            None => {
+                result.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    result.push_str(rest);
+
+    result
+}
+
+fn pt_br(text: Text) -> &'static str {
+    match text {
+        Text::Message(Code::EL0) => "{0} não pertence ao alfabeto",
+        Text::Message(Code::EL1) => "{0} não é início de nenhum token",
+        Text::Message(Code::EL2) => "após um '.' em um [num] deve-se conter um dígito - {0} foi encontrado",
+        Text::Message(Code::EL3) => "após um 'e' ou 'E' em um [num] deve-se conter um dígito, um '+' ou um '-' - {0} foi encontrado",
+        Text::Message(Code::EL4) => "após um 'e+' ou 'E+' em um [num] deve-se conter um dígito - {0} foi encontrado",
+        Text::Message(Code::EL5) => "após um 'e-' ou 'E-' em um [num] deve-se conter um dígito - {0} foi encontrado",
+        Text::Message(Code::EL6) => "não foi encontrado o fechamento do comentário ou literal",
+        Text::Message(Code::EL7) => "não foi possível ler o código-fonte: {0}",
+        Text::Message(Code::ES0) => "token inesperado '{0}'",
+        Text::Message(Code::ES1) => "nenhum código deve vir após a palavra reservada 'fim'",
+        Text::Message(Code::ES2) => "ausência de ';'",
+        Text::Message(Code::ES3) => "múltiplos ';' na sequência",
+        Text::Message(Code::ES4) => "token inválido após um ';'",
+        Text::Message(Code::ES5) | Text::Message(Code::ES6) => "esperado um '(' após a palavra reservada 'se'",
+        Text::Message(Code::ES7) => "após um identificador deve vir um operador relacional, um operador aritmético, um ')' ou um ';'",
+        Text::Message(Code::ES8) => "após um número deve vir um operador relacional, um operador aritmético, um ')' ou um ';'",
+        Text::Message(Code::ES9) => "falta '{0}' antes de '{1}'",
+        Text::Message(Code::ES10) => "'{0}' não era esperado aqui",
+        Text::Message(Code::ES11) => "esperado '{0}' em vez de '{1}'",
+        Text::Message(Code::EM1) => "a variável '{0}' não foi declarada",
+        Text::Message(Code::EM2) => "a variável '{0}' já foi declarada",
+        Text::Message(Code::EM3) => "não é possível atribuir um valor do tipo {0} à variável '{1}' do tipo {2}",
+        Text::Message(Code::EM4) => "o operador aritmético '{0}' não pode ser aplicado a operandos do tipo literal",
+        Text::Message(Code::EM5) => "operandos com tipos incompatíveis no operador relacional '{0}' ({1} e {2})",
+
+        Text::InThisNumber => "neste número",
+        Text::FirstDeclaredHere => "declarada pela primeira vez aqui",
+        Text::DeclaredAs => "'{0}' foi declarada como {1} aqui",
+        Text::HasTypeLiteral => "'{0}' é do tipo literal",
+        Text::ProgramEndsHere => "o programa termina aqui",
+        Text::Skipped => "ignorado ao recuperar do erro",
+
+        Text::DeclareBetween => "as variáveis devem ser declaradas entre 'varinicio' e 'varfim'",
+        Text::Expected => "esperado: {0}",
+        Text::TokenRemoved => "o token '{0}' foi removido",
+        Text::Inserted => "'{0}' foi inserido antes de '{1}'",
+        Text::Replaced => "'{0}' foi substituído por '{1}'",
+        Text::Unrecoverable => "não é possível recuperar deste erro e portanto a análise foi interrompida",
+        Text::Resumed => "a análise foi retomada em '{0}'",
+
+        Text::RemoveCharacter => "remova o caractere {0}",
+        Text::CloseWith => "feche com '{0}'",
+        Text::RemoveDeclaration => "remova a declaração repetida",
+        Text::Insert => "insira um '{0}'",
+        Text::RemoveRepeated => "remova o '{0}' repetido",
+        Text::Remove => "remova '{0}'",
+        Text::Replace => "substitua '{0}' por '{1}'",
+
+        Text::Error => "erro",
+        Text::Warning => "aviso",
+        Text::Note => "nota",
+        Text::Help => "ajuda",
+        Text::ErrorAt(Phase::Lexical) => "Erro léxico na linha {0}, coluna {1}: {2}",
+        Text::ErrorAt(Phase::Syntactic) => "Erro sintático na linha {0}, coluna {1}: {2}",
+        Text::ErrorAt(Phase::Semantic) => "Erro semântico na linha {0}, coluna {1}: {2}",
+        Text::NoErrors => "Nenhum erro encontrado",
+        Text::OneError => "Foi encontrado 1 erro",
+        Text::ManyErrors => "Foram encontrados {0} erros",
+        Text::OneErrorIn(Phase::Lexical) => "Foi encontrado 1 erro léxico",
+        Text::OneErrorIn(Phase::Syntactic) => "Foi encontrado 1 erro sintático",
+        Text::OneErrorIn(Phase::Semantic) => "Foi encontrado 1 erro semântico",
+        Text::ManyErrorsIn(Phase::Lexical) => "Foram encontrados {0} erros léxicos",
+        Text::ManyErrorsIn(Phase::Syntactic) => "Foram encontrados {0} erros sintáticos",
+        Text::ManyErrorsIn(Phase::Semantic) => "Foram encontrados {0} erros semânticos",
+    }
+}
+
+fn en(text: Text) -> &'static str {
+    match text {
+        Text::Message(Code::EL0) => "{0} is not in the alphabet",
+        Text::Message(Code::EL1) => "{0} does not start any token",
+        Text::Message(Code::EL2) => "a '.' in a [num] must be followed by a digit - found {0}",
+        Text::Message(Code::EL3) => "an 'e' or 'E' in a [num] must be followed by a digit, a '+' or a '-' - found {0}",
+        Text::Message(Code::EL4) => "an 'e+' or 'E+' in a [num] must be followed by a digit - found {0}",
+        Text::Message(Code::EL5) => "an 'e-' or 'E-' in a [num] must be followed by a digit - found {0}",
+        Text::Message(Code::EL6) => "unterminated comment or literal",
+        Text::Message(Code::EL7) => "could not read the source: {0}",
+        Text::Message(Code::ES0) => "unexpected token '{0}'",
+        Text::Message(Code::ES1) => "no code may follow the keyword 'fim'",
+        Text::Message(Code::ES2) => "missing ';'",
+        Text::Message(Code::ES3) => "multiple ';' in a row",
+        Text::Message(Code::ES4) => "invalid token after a ';'",
+        Text::Message(Code::ES5) | Text::Message(Code::ES6) => "expected a '(' after the keyword 'se'",
+        Text::Message(Code::ES7) => "an identifier must be followed by a relational operator, an arithmetic operator, a ')' or a ';'",
+        Text::Message(Code::ES8) => "a number must be followed by a relational operator, an arithmetic operator, a ')' or a ';'",
+        Text::Message(Code::ES9) => "missing '{0}' before '{1}'",
+        Text::Message(Code::ES10) => "'{0}' was not expected here",
+        Text::Message(Code::ES11) => "expected '{0}' instead of '{1}'",
+        Text::Message(Code::EM1) => "the variable '{0}' was not declared",
+        Text::Message(Code::EM2) => "the variable '{0}' was already declared",
+        Text::Message(Code::EM3) => "cannot assign a value of type {0} to the variable '{1}' of type {2}",
+        Text::Message(Code::EM4) => "the arithmetic operator '{0}' cannot be applied to operands of type literal",
+        Text::Message(Code::EM5) => "operands of incompatible types for the relational operator '{0}' ({1} and {2})",
+
+        Text::InThisNumber => "in this number",
+        Text::FirstDeclaredHere => "first declared here",
+        Text::DeclaredAs => "'{0}' declared as {1} here",
+        Text::HasTypeLiteral => "'{0}' has type literal",
+        Text::ProgramEndsHere => "the program ends here",
+        Text::Skipped => "skipped to recover from the error",
+
+        Text::DeclareBetween => "variables must be declared between 'varinicio' and 'varfim'",
+        Text::Expected => "expected: {0}",
+        Text::TokenRemoved => "the token '{0}' was removed",
+        Text::Inserted => "inserted '{0}' before '{1}'",
+        Text::Replaced => "replaced '{0}' with '{1}'",
+        Text::Unrecoverable => "this error cannot be recovered from, so the analysis stopped",
+        Text::Resumed => "parsing resumed at '{0}'",
+
+        Text::RemoveCharacter => "remove the character {0}",
+        Text::CloseWith => "close it with '{0}'",
+        Text::RemoveDeclaration => "remove the repeated declaration",
+        Text::Insert => "insert a '{0}'",
+        Text::RemoveRepeated => "remove the repeated '{0}'",
+        Text::Remove => "remove '{0}'",
+        Text::Replace => "replace '{0}' with '{1}'",
+
+        Text::Error => "error",
+        Text::Warning => "warning",
+        Text::Note => "note",
+        Text::Help => "help",
+        Text::ErrorAt(Phase::Lexical) => "Lexical error at line {0}, column {1}: {2}",
+        Text::ErrorAt(Phase::Syntactic) => "Syntax error at line {0}, column {1}: {2}",
+        Text::ErrorAt(Phase::Semantic) => "Semantic error at line {0}, column {1}: {2}",
+        Text::NoErrors => "No errors found",
+        Text::OneError => "Found 1 error",
+        Text::ManyErrors => "Found {0} errors",
+        Text::OneErrorIn(Phase::Lexical) => "Found 1 lexical error",
+        Text::OneErrorIn(Phase::Syntactic) => "Found 1 syntax error",
+        Text::OneErrorIn(Phase::Semantic) => "Found 1 semantic error",
+        Text::ManyErrorsIn(Phase::Lexical) => "Found {0} lexical errors",
+        Text::ManyErrorsIn(Phase::Syntactic) => "Found {0} syntax errors",
+        Text::ManyErrorsIn(Phase::Semantic) => "Found {0} semantic errors",
+    }
+}
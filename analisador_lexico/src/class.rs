@@ -65,6 +65,47 @@ impl Class {
         }
     }
 
+    /// How the tokens of `class` are written in the source, for the classes
+    /// that have a single spelling.
+    pub fn spelling(class: &Class) -> Option<&'static str> {
+        match class {
+            Class::Rcb => Some("<-"),
+            Class::Abp => Some("("),
+            Class::Fcp => Some(")"),
+            Class::Ptv => Some(";"),
+            Class::Vir => Some(","),
+            Class::Inicio => Some("inicio"),
+            Class::Varinicio => Some("varinicio"),
+            Class::Varfim => Some("varfim"),
+            Class::Escreva => Some("escreva"),
+            Class::Leia => Some("leia"),
+            Class::Se => Some("se"),
+            Class::Entao => Some("entao"),
+            Class::Fimse => Some("fimse"),
+            Class::Repita => Some("repita"),
+            Class::Fimrepita => Some("fimrepita"),
+            Class::Fim => Some("fim"),
+            Class::Inteiro => Some("inteiro"),
+            Class::Literal => Some("literal"),
+            Class::Real => Some("real"),
+            _ => None,
+        }
+    }
+
+    /// How tokens of `class` are shown to users: every spelling of the
+    /// operators, or the class name for tokens spelled freely.
+    pub fn spellings(class: &Class) -> Vec<&'static str> {
+        match class {
+            Class::Opr => vec!["<", ">", "<=", ">=", "<>"],
+            Class::Opm => vec!["+", "-", "*", "/"],
+            Class::Num => vec!["num"],
+            Class::Lit => vec!["lit"],
+            Class::Id => vec!["id"],
+            Class::Eof => vec!["EOF"],
+            class => Class::spelling(class).into_iter().collect(),
+        }
+    }
+
     pub fn to_str(class: Option<Class>) -> String {
         match class {
             Some(Class::Num) => String::from("num"),
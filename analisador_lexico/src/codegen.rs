@@ -0,0 +1,306 @@
+use std::{collections::HashMap, io::BufRead};
+
+use crate::{
+    ast::{Argument, Command, Condition, Expression, Operand, Program},
+    diagnostic::Diagnostics,
+    parser::Parser,
+    scanner::Scanner,
+    token::Token,
+    token_type::TokenType,
+};
+
+pub struct CodeGenerator {
+    types: HashMap<String, TokenType>,
+    temporaries: Vec<TokenType>,
+    reads_numbers: bool,
+    body: Vec<String>,
+    indentation: usize,
+}
+
+impl Default for CodeGenerator {
+    fn default() -> CodeGenerator {
+        CodeGenerator::new()
+    }
+}
+
+impl CodeGenerator {
+    pub fn new() -> CodeGenerator {
+        CodeGenerator {
+            types: HashMap::new(),
+            temporaries: Vec::new(),
+            reads_numbers: false,
+            body: Vec::new(),
+            indentation: 1,
+        }
+    }
+
+    pub fn generate(&mut self, program: &Program) -> String {
+        let mut declarations: Vec<String> = Vec::new();
+
+        for declaration in &program.declarations {
+            let token_type = declaration
+                .data_type
+                .token_type
+                .clone()
+                .unwrap_or(TokenType::Inteiro);
+
+            for id in &declaration.ids {
+                let lexeme = lexeme(id);
+                if self.types.contains_key(&lexeme) {
+                    continue;
+                }
+
+                // Initialized as the interpreter does, so both agree.
+                declarations.push(match token_type {
+                    TokenType::Literal => format!("char {}[256] = \"\";", variable(id)),
+                    TokenType::Real => format!("double {} = 0.0;", variable(id)),
+                    _ => format!("int {} = 0;", variable(id)),
+                });
+                self.types.insert(lexeme, token_type.clone());
+            }
+        }
+
+        for command in &program.commands {
+            self.command(command);
+        }
+
+        let mut output = String::new();
+        output.push_str("#include <stdio.h>\n");
+        output.push_str("#include <string.h>\n\n");
+        output.push_str("int main(void)\n{\n");
+        output.push_str("    /*----Variaveis temporarias----*/\n");
+        for (i, token_type) in self.temporaries.iter().enumerate() {
+            match token_type {
+                TokenType::Real => output.push_str(&format!("    double {};\n", temporary(i))),
+                _ => output.push_str(&format!("    int {};\n", temporary(i))),
+            }
+        }
+        output.push_str("    /*------------------------------*/\n");
+        if self.reads_numbers {
+            output.push_str("    char linha[256];\n");
+        }
+        for declaration in declarations {
+            output.push_str(&format!("    {}\n", declaration));
+        }
+        output.push('\n');
+        for line in &self.body {
+            output.push_str(line);
+            output.push('\n');
+        }
+        output.push_str("\n    return 0;\n}\n");
+
+        output
+    }
+
+    fn command(&mut self, command: &Command) {
+        match command {
+            // Every `leia` takes a whole line, as in the interpreter, so a
+            // literal keeps its spaces and a number leaves nothing behind.
+            Command::Read { id, .. } => {
+                let variable = variable(id);
+                match self.type_of(id) {
+                    TokenType::Literal => {
+                        self.emit(format!("if (fgets({0}, sizeof {0}, stdin) != NULL)", variable));
+                        self.emit(format!("    {0}[strcspn({0}, \"\\r\\n\")] = '\\0';", variable));
+                    }
+                    token_type => {
+                        let format = match token_type {
+                            TokenType::Real => "%lf",
+                            _ => "%d",
+                        };
+                        self.reads_numbers = true;
+                        self.emit(String::from("if (fgets(linha, sizeof linha, stdin) != NULL)"));
+                        self.emit(format!("    sscanf(linha, \"{}\", &{});", format, variable));
+                    }
+                }
+            }
+            Command::Write { argument, .. } => match argument {
+                Argument::Literal(lit) => self.emit(format!("printf(\"%s\", {});", lexeme(lit))),
+                Argument::Number(num) => match num.token_type {
+                    Some(TokenType::Real) => {
+                        self.emit(format!("printf(\"%lf\", {});", number(num)))
+                    }
+                    _ => self.emit(format!("printf(\"%d\", {});", number(num))),
+                },
+                Argument::Id(id) => {
+                    let format = match self.type_of(id) {
+                        TokenType::Literal => "%s",
+                        TokenType::Real => "%lf",
+                        _ => "%d",
+                    };
+                    self.emit(format!("printf(\"{}\", {});", format, variable(id)));
+                }
+            },
+            Command::Assign { id, value, .. } => {
+                let value = match value {
+                    Expression::Operand(operand) => self.operand(operand),
+                    Expression::Arithmetic {
+                        left,
+                        operator,
+                        right,
+                    } => {
+                        let token_type =
+                            match (self.type_of_operand(left), self.type_of_operand(right)) {
+                                (TokenType::Inteiro, TokenType::Inteiro) => TokenType::Inteiro,
+                                _ => TokenType::Real,
+                            };
+                        let temporary = temporary(self.temporaries.len());
+                        self.temporaries.push(token_type);
+
+                        let left = self.operand(left);
+                        let right = self.operand(right);
+                        self.emit(format!(
+                            "{} = {} {} {};",
+                            temporary,
+                            left,
+                            lexeme(operator),
+                            right
+                        ));
+
+                        temporary
+                    }
+                };
+
+                match self.type_of(id) {
+                    TokenType::Literal => self.emit(format!("strcpy({}, {});", variable(id), value)),
+                    _ => self.emit(format!("{} = {};", variable(id), value)),
+                }
+            }
+            Command::If {
+                condition, body, ..
+            } => {
+                let condition = self.condition(condition);
+                self.block(format!("if ({})", condition), body);
+            }
+            Command::Repeat {
+                condition, body, ..
+            } => {
+                let condition = self.condition(condition);
+                self.block(format!("while ({})", condition), body);
+            }
+        }
+    }
+
+    fn block(&mut self, header: String, body: &[Command]) {
+        self.emit(header);
+        self.emit(String::from("{"));
+        self.indentation += 1;
+        for command in body {
+            self.command(command);
+        }
+        self.indentation -= 1;
+        self.emit(String::from("}"));
+    }
+
+    fn condition(&self, condition: &Condition) -> String {
+        let operator = match lexeme(&condition.operator).as_str() {
+            "=" => String::from("=="),
+            "<>" => String::from("!="),
+            operator => String::from(operator),
+        };
+
+        format!(
+            "{} {} {}",
+            self.operand(&condition.left),
+            operator,
+            self.operand(&condition.right)
+        )
+    }
+
+    fn operand(&self, operand: &Operand) -> String {
+        match operand {
+            Operand::Id(id) => variable(id),
+            Operand::Number(num) => number(num),
+        }
+    }
+
+    fn type_of_operand(&self, operand: &Operand) -> TokenType {
+        match operand {
+            Operand::Id(id) => self.type_of(id),
+            Operand::Number(num) => num.token_type.clone().unwrap_or(TokenType::Inteiro),
+        }
+    }
+
+    fn type_of(&self, id: &Token) -> TokenType {
+        self.types
+            .get(&lexeme(id))
+            .cloned()
+            .unwrap_or(TokenType::Inteiro)
+    }
+
+    fn emit(&mut self, line: String) {
+        self.body
+            .push(format!("{}{}", "    ".repeat(self.indentation), line));
+    }
+}
+
+/// Translates a whole program, or returns the diagnostics that prevent it.
+pub fn translate<R: BufRead>(scanner: &mut Scanner<R>) -> Result<String, Diagnostics> {
+    let mut parser = Parser::new();
+    let program = parser.parse(scanner);
+
+    match program {
+        Some(program) if !parser.diagnostics().has_errors() => {
+            Ok(CodeGenerator::new().generate(&program))
+        }
+        _ => Err(parser.take_diagnostics()),
+    }
+}
+
+fn lexeme(token: &Token) -> String {
+    token.lexeme.clone().unwrap_or_default()
+}
+
+/// Names C reserves, or that the generated code relies on, which Mgol
+/// identifiers may still spell.
+const C_RESERVED: [&str; 62] = [
+    "auto", "break", "case", "char", "const", "continue", "default", "do", "double", "else", "enum",
+    "extern", "float", "for", "goto", "if", "inline", "int", "long", "register", "restrict",
+    "return", "short", "signed", "sizeof", "static", "struct", "switch", "typedef", "union",
+    "unsigned", "void", "volatile", "while", "main", "printf", "scanf", "sscanf", "fgets", "strcpy",
+    "strcspn", "linha", "EOF", "NULL", "BUFSIZ", "FILENAME_MAX", "FOPEN_MAX", "L_tmpnam", "TMP_MAX",
+    "SEEK_CUR", "SEEK_END", "SEEK_SET", "stdin", "stdout", "stderr", "bool", "true", "false",
+    "alignas", "alignof", "static_assert", "thread_local",
+];
+
+/// The C name of a variable. Names C reserves or a temporary takes get a
+/// `_` suffix, and so do those that only differ from one of them in their
+/// trailing `_`s, so `T0` becomes `T0_` and a `T0_` of the program `T0__`.
+fn variable(id: &Token) -> String {
+    let lexeme = lexeme(id);
+    let stem = lexeme.trim_end_matches('_');
+
+    match C_RESERVED.contains(&stem) || is_temporary(stem) {
+        true => format!("{}_", lexeme),
+        false => lexeme,
+    }
+}
+
+fn temporary(n: usize) -> String {
+    format!("T{}", n)
+}
+
+fn is_temporary(name: &str) -> bool {
+    match name.strip_prefix('T') {
+        Some(digits) => !digits.is_empty() && digits.chars().all(|c| c.is_ascii_digit()),
+        None => false,
+    }
+}
+
+/// Integers are written in decimal without leading zeros, which C would read
+/// as octal.
+fn number(token: &Token) -> String {
+    let lexeme = lexeme(token);
+
+    match token.token_type {
+        Some(TokenType::Inteiro) if lexeme.contains(['e', 'E']) => lexeme
+            .parse::<f64>()
+            .map(|value| format!("{}", value as i64))
+            .unwrap_or(lexeme),
+        Some(TokenType::Inteiro) => match lexeme.trim_start_matches('0') {
+            "" => String::from("0"),
+            digits => String::from(digits),
+        },
+        _ => lexeme,
+    }
+}
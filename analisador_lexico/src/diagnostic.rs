@@ -0,0 +1,329 @@
+use std::fmt;
+
+use crate::{
+    catalog::{self, Locale, Text},
+    span::Span,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+impl Severity {
+    pub fn to_str(severity: Severity) -> String {
+        match severity {
+            Severity::Error => String::from("error"),
+            Severity::Warning => String::from("warning"),
+            Severity::Note => String::from("note"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Phase {
+    Lexical,
+    Syntactic,
+    Semantic,
+}
+
+impl Phase {
+    /// The name used in machine-readable output.
+    pub fn to_str(phase: Phase) -> String {
+        match phase {
+            Phase::Lexical => String::from("lexico"),
+            Phase::Syntactic => String::from("sintatico"),
+            Phase::Semantic => String::from("semantico"),
+        }
+    }
+}
+
+/// Stable identifiers of every diagnostic: `EL` lexical, `ES` syntactic
+/// (one per recovery routine, ES9..ES11 for local repairs) and `EM`
+/// semantic.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Code {
+    EL0,
+    EL1,
+    EL2,
+    EL3,
+    EL4,
+    EL5,
+    EL6,
+    EL7,
+    ES0,
+    ES1,
+    ES2,
+    ES3,
+    ES4,
+    ES5,
+    ES6,
+    ES7,
+    ES8,
+    ES9,
+    ES10,
+    ES11,
+    EM1,
+    EM2,
+    EM3,
+    EM4,
+    EM5,
+}
+
+impl Code {
+    pub const ALL: [Code; 25] = [
+        Code::EL0,
+        Code::EL1,
+        Code::EL2,
+        Code::EL3,
+        Code::EL4,
+        Code::EL5,
+        Code::EL6,
+        Code::EL7,
+        Code::ES0,
+        Code::ES1,
+        Code::ES2,
+        Code::ES3,
+        Code::ES4,
+        Code::ES5,
+        Code::ES6,
+        Code::ES7,
+        Code::ES8,
+        Code::ES9,
+        Code::ES10,
+        Code::ES11,
+        Code::EM1,
+        Code::EM2,
+        Code::EM3,
+        Code::EM4,
+        Code::EM5,
+    ];
+
+    pub fn to_str(code: Code) -> String {
+        format!("{:?}", code)
+    }
+
+    pub fn phase(code: Code) -> Phase {
+        match code {
+            Code::EL0
+            | Code::EL1
+            | Code::EL2
+            | Code::EL3
+            | Code::EL4
+            | Code::EL5
+            | Code::EL6
+            | Code::EL7 => Phase::Lexical,
+            Code::ES0
+            | Code::ES1
+            | Code::ES2
+            | Code::ES3
+            | Code::ES4
+            | Code::ES5
+            | Code::ES6
+            | Code::ES7
+            | Code::ES8
+            | Code::ES9
+            | Code::ES10
+            | Code::ES11 => Phase::Syntactic,
+            Code::EM1 | Code::EM2 | Code::EM3 | Code::EM4 | Code::EM5 => Phase::Semantic,
+        }
+    }
+}
+
+/// A text of the catalog with its arguments, rendered in whatever locale
+/// the diagnostic is shown.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Message {
+    pub text: Text,
+    pub args: Vec<String>,
+}
+
+impl Message {
+    pub fn new(text: Text, args: Vec<String>) -> Message {
+        Message { text, args }
+    }
+
+    pub fn render(&self, locale: Locale) -> String {
+        catalog::format(locale, self.text, &self.args)
+    }
+}
+
+impl From<Text> for Message {
+    fn from(text: Text) -> Message {
+        Message::new(text, Vec::new())
+    }
+}
+
+/// A secondary span with an explanation, e.g. where a variable was declared.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Label {
+    pub span: Span,
+    pub message: Message,
+}
+
+/// A suggested edit: replace the text in `span` by `replacement`. An empty
+/// span inserts and an empty replacement deletes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fix {
+    pub span: Span,
+    pub replacement: String,
+    pub message: Message,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: Code,
+    /// The arguments of the message of `code`.
+    pub args: Vec<String>,
+    pub span: Span,
+    pub labels: Vec<Label>,
+    pub notes: Vec<Message>,
+    pub fixes: Vec<Fix>,
+}
+
+impl Diagnostic {
+    pub fn error(code: Code, span: Span, args: Vec<String>) -> Diagnostic {
+        Diagnostic {
+            severity: Severity::Error,
+            code,
+            args,
+            span,
+            labels: Vec::new(),
+            notes: Vec::new(),
+            fixes: Vec::new(),
+        }
+    }
+
+    pub fn with_label(mut self, span: Span, message: Message) -> Diagnostic {
+        self.labels.push(Label { span, message });
+        self
+    }
+
+    pub fn with_note(mut self, note: Message) -> Diagnostic {
+        self.notes.push(note);
+        self
+    }
+
+    pub fn with_fix(mut self, span: Span, replacement: &str, message: Message) -> Diagnostic {
+        self.fixes.push(Fix {
+            span,
+            replacement: String::from(replacement),
+            message,
+        });
+        self
+    }
+
+    pub fn phase(&self) -> Phase {
+        Code::phase(self.code)
+    }
+
+    pub fn message(&self, locale: Locale) -> String {
+        catalog::format(locale, Text::Message(self.code), &self.args)
+    }
+
+    /// One line per diagnostic plus its notes, e.g.
+    /// `[ES2] Erro sintático na linha 5, coluna 7: ausência de ';'`.
+    pub fn text(&self, locale: Locale) -> String {
+        let mut text = format!(
+            "[{}] {}",
+            Code::to_str(self.code),
+            catalog::format(
+                locale,
+                Text::ErrorAt(self.phase()),
+                &[
+                    self.span.line.to_string(),
+                    self.span.column.to_string(),
+                    self.message(locale),
+                ],
+            )
+        );
+        let note = catalog::template(locale, Text::Note).to_uppercase();
+        for message in &self.notes {
+            text.push_str(&format!("\n    {}: {}", note, message.render(locale)));
+        }
+
+        text
+    }
+}
+
+/// The diagnostic in the default locale, see `Diagnostic::text`.
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.text(Locale::default()))
+    }
+}
+
+/// The sink every phase reports to. Diagnostics are kept in the order they
+/// were found, which for a single pass over the source is reading order.
+#[derive(Clone, Debug, Default)]
+pub struct Diagnostics {
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl Diagnostics {
+    pub fn new() -> Diagnostics {
+        Diagnostics {
+            diagnostics: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, diagnostic: Diagnostic) {
+        self.diagnostics.push(diagnostic);
+    }
+
+    pub fn append(&mut self, other: &mut Diagnostics) {
+        self.diagnostics.append(&mut other.diagnostics);
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Diagnostic> {
+        self.diagnostics.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.diagnostics.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+
+    pub fn error_count(&self) -> usize {
+        self.count(Severity::Error)
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == severity)
+            .count()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.error_count() > 0
+    }
+
+    pub fn clear(&mut self) {
+        self.diagnostics.clear();
+    }
+}
+
+impl IntoIterator for Diagnostics {
+    type Item = Diagnostic;
+    type IntoIter = std::vec::IntoIter<Diagnostic>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.diagnostics.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Diagnostics {
+    type Item = &'a Diagnostic;
+    type IntoIter = std::slice::Iter<'a, Diagnostic>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.diagnostics.iter()
+    }
+}
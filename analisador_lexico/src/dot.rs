@@ -0,0 +1,22 @@
+/// `text` as a quoted Graphviz string, see `escape`.
+pub fn string(text: &str) -> String {
+    format!("\"{}\"", escape(text))
+}
+
+/// `text` with quotes, backslashes and line breaks escaped, to be put in a
+/// quoted Graphviz string.
+pub fn escape(text: &str) -> String {
+    let mut dot = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '"' => dot.push_str("\\\""),
+            '\\' => dot.push_str("\\\\"),
+            '\n' => dot.push_str("\\n"),
+            '\r' => (),
+            c => dot.push(c),
+        }
+    }
+
+    dot
+}
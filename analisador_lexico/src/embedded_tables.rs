@@ -0,0 +1,14 @@
+//! The SLR(1) tables of the Mgol grammar, computed by build.rs from
+//! src/mgol.bnf when the crate is compiled.
+
+use crate::slr_table::{ActionTable, GotoTable, SLRAction};
+
+include!(concat!(env!("OUT_DIR"), "/mgol_tables.rs"));
+
+pub fn action_table() -> ActionTable {
+    ActionTable::from_cells(STATES, TERMINALS, ACTIONS.to_vec())
+}
+
+pub fn goto_table() -> GotoTable {
+    GotoTable::from_cells(STATES, NON_TERMINALS, GOTOS.to_vec())
+}
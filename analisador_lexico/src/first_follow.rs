@@ -0,0 +1,145 @@
+use std::collections::{BTreeSet, HashMap};
+
+use crate::grammar::{AlphabetItem, Grammar, END_MARKER};
+
+pub struct FirstFollow {
+    pub nullable: BTreeSet<String>,
+    pub first: HashMap<String, BTreeSet<String>>,
+    pub follow: HashMap<String, BTreeSet<String>>,
+}
+
+impl FirstFollow {
+    pub fn new(grammar: &Grammar) -> FirstFollow {
+        let mut sets = FirstFollow {
+            nullable: BTreeSet::new(),
+            first: HashMap::new(),
+            follow: HashMap::new(),
+        };
+
+        for non_terminal in grammar.non_terminals() {
+            sets.first.insert(non_terminal.clone(), BTreeSet::new());
+            sets.follow.insert(non_terminal, BTreeSet::new());
+        }
+
+        sets.compute_first(grammar);
+        sets.compute_follow(grammar);
+
+        sets
+    }
+
+    pub fn first_of(&self, symbols: &[AlphabetItem]) -> (BTreeSet<String>, bool) {
+        let mut first: BTreeSet<String> = BTreeSet::new();
+
+        for symbol in symbols {
+            if symbol.terminal {
+                first.insert(symbol.text.clone());
+                return (first, false);
+            }
+
+            if let Some(set) = self.first.get(&symbol.text) {
+                first.extend(set.iter().cloned());
+            }
+
+            if !self.nullable.contains(&symbol.text) {
+                return (first, false);
+            }
+        }
+
+        (first, true)
+    }
+
+    pub fn follow(&self, non_terminal: &str) -> BTreeSet<String> {
+        self.follow.get(non_terminal).cloned().unwrap_or_default()
+    }
+
+    /// A table with whether every nonterminal of `grammar` is nullable and its
+    /// FIRST and FOLLOW sets, in the order the nonterminals are declared.
+    pub fn report(&self, grammar: &Grammar) -> String {
+        let set = |set: Option<&BTreeSet<String>>| {
+            let symbols: Vec<&str> = set.into_iter().flatten().map(String::as_str).collect();
+            format!("{{ {} }}", symbols.join(", "))
+        };
+
+        let rows: Vec<(String, &str, String, String)> = grammar
+            .non_terminals()
+            .into_iter()
+            .map(|non_terminal| {
+                let nullable = match self.nullable.contains(&non_terminal) {
+                    true => "sim",
+                    false => "não",
+                };
+                let first = set(self.first.get(&non_terminal));
+                let follow = set(self.follow.get(&non_terminal));
+                (non_terminal, nullable, first, follow)
+            })
+            .collect();
+
+        let width = rows.iter().map(|row| row.0.chars().count()).max().unwrap_or(0).max(12);
+        let first = rows.iter().map(|row| row.2.chars().count()).max().unwrap_or(0).max(5);
+
+        let mut report = format!(
+            "{:<width$} {:<8} {:<first$} FOLLOW\n",
+            "Não terminal", "Anulável", "FIRST"
+        );
+        for (non_terminal, nullable, first_set, follow_set) in rows {
+            report.push_str(&format!(
+                "{:<width$} {:<8} {:<first$} {}\n",
+                non_terminal, nullable, first_set, follow_set
+            ));
+        }
+
+        report
+    }
+
+    fn compute_first(&mut self, grammar: &Grammar) {
+        let mut changed = true;
+
+        while changed {
+            changed = false;
+
+            for rule in grammar.rules() {
+                let (first, nullable) = self.first_of(&rule.right);
+
+                let set = self.first.entry(rule.left.text.clone()).or_default();
+                let before = set.len();
+                set.extend(first);
+                changed |= set.len() != before;
+
+                if nullable {
+                    changed |= self.nullable.insert(rule.left.text.clone());
+                }
+            }
+        }
+    }
+
+    fn compute_follow(&mut self, grammar: &Grammar) {
+        self.follow
+            .entry(String::from(grammar.start()))
+            .or_default()
+            .insert(String::from(END_MARKER));
+
+        let mut changed = true;
+
+        while changed {
+            changed = false;
+
+            for rule in grammar.rules() {
+                for (i, symbol) in rule.right.iter().enumerate() {
+                    if symbol.terminal {
+                        continue;
+                    }
+
+                    let (mut follow, nullable) = self.first_of(&rule.right[i + 1..]);
+                    if nullable {
+                        follow.extend(self.follow(&rule.left.text));
+                    }
+
+                    let set = self.follow.entry(symbol.text.clone()).or_default();
+                    let before = set.len();
+                    set.extend(follow);
+                    changed |= set.len() != before;
+                }
+            }
+        }
+    }
+}
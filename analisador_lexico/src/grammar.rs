@@ -1,7 +1,16 @@
+use std::{collections::HashSet, fmt, fs};
+
+use crate::interner::Interner;
+
+pub const END_MARKER: &str = "eof";
+
+const MGOL_GRAMMAR: &str = include_str!("mgol.bnf");
+
 #[derive(Clone)]
 pub struct AlphabetItem {
     pub text: String,
     pub terminal: bool,
+    pub id: usize,
 }
 
 #[derive(Clone)]
@@ -12,7 +21,14 @@ pub struct GrammarRule {
 
 impl GrammarRule {
     pub fn show(&self) {
-        println!(
+        println!("{}", self);
+    }
+}
+
+impl fmt::Display for GrammarRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
             "{} -> {}",
             self.left.text,
             self.right
@@ -20,26 +36,301 @@ impl GrammarRule {
                 .map(|item| String::from(&item.text))
                 .collect::<Vec<String>>()
                 .join(" ")
-        );
+        )
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum GrammarError {
+    Io(String),
+    Syntax { line: usize, message: String },
+    UndeclaredSymbol { line: usize, symbol: String },
+    TerminalWithRules { line: usize, symbol: String },
+    UnknownStart(String),
+    Unreachable(String),
+    NonProductive(String),
+    Empty,
+}
+
+impl fmt::Display for GrammarError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GrammarError::Io(message) => {
+                write!(f, "Não foi possível ler a gramática: {}", message)
+            }
+            GrammarError::Syntax { line, message } => {
+                write!(f, "Erro na gramática, linha {}: {}", line, message)
+            }
+            GrammarError::UndeclaredSymbol { line, symbol } => write!(
+                f,
+                "Erro na gramática, linha {}: o símbolo '{}' não é um %token nem possui produções",
+                line, symbol
+            ),
+            GrammarError::TerminalWithRules { line, symbol } => write!(
+                f,
+                "Erro na gramática, linha {}: o terminal '{}' não pode ter produções",
+                line, symbol
+            ),
+            GrammarError::UnknownStart(symbol) => write!(
+                f,
+                "Erro na gramática: o símbolo inicial '{}' não possui produções",
+                symbol
+            ),
+            GrammarError::Unreachable(symbol) => write!(
+                f,
+                "Erro na gramática: o não-terminal '{}' não é alcançável a partir do símbolo inicial",
+                symbol
+            ),
+            GrammarError::NonProductive(symbol) => write!(
+                f,
+                "Erro na gramática: o não-terminal '{}' não deriva nenhuma sentença",
+                symbol
+            ),
+            GrammarError::Empty => write!(f, "Erro na gramática: nenhuma produção foi definida"),
+        }
     }
 }
 
 pub struct Grammar {
     rules: Vec<GrammarRule>,
+    terminals: Interner,
+    non_terminals: Interner,
 }
 
 impl Grammar {
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Grammar {
-        let mut grammar = Grammar { rules: vec![] };
-        grammar.init_rules();
+        match Grammar::from_source(MGOL_GRAMMAR) {
+            Ok(grammar) => grammar,
+            Err(errors) => panic!("A gramática do Mgol é inválida: {:?}", errors),
+        }
+    }
+
+    pub fn from_file(path: &str) -> Result<Grammar, Vec<GrammarError>> {
+        match fs::read_to_string(path) {
+            Ok(source) => Grammar::from_source(&source),
+            Err(err) => Err(vec![GrammarError::Io(format!("{}: {}", path, err))]),
+        }
+    }
+
+    /// Reads a grammar written as `%token`/`%start` declarations followed by
+    /// `A -> x y | z` productions, with `#` comments and `ε` for the empty word.
+    pub fn from_source(source: &str) -> Result<Grammar, Vec<GrammarError>> {
+        let mut errors: Vec<GrammarError> = Vec::new();
+        let mut tokens: Vec<String> = Vec::new();
+        let mut start: Option<String> = None;
+        let mut productions: Vec<(usize, String, Vec<String>)> = Vec::new();
+        let mut left: Option<String> = None;
+
+        for (i, line) in source.lines().enumerate() {
+            let n = i + 1;
+            let line = match line.find('#') {
+                Some(comment) => &line[..comment],
+                None => line,
+            }
+            .trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            // A directive is a whole word: `%tokens` is not `%token`.
+            let (directive, arguments) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+
+            if directive == "%token" {
+                for token in arguments.split_whitespace() {
+                    if token != END_MARKER && !tokens.iter().any(|t| t == token) {
+                        tokens.push(String::from(token));
+                    }
+                }
+                continue;
+            }
+
+            if directive == "%start" {
+                match arguments.split_whitespace().collect::<Vec<&str>>()[..] {
+                    [symbol] => start = Some(String::from(symbol)),
+                    _ => errors.push(GrammarError::Syntax {
+                        line: n,
+                        message: String::from("%start deve ser seguido de um único símbolo"),
+                    }),
+                }
+                continue;
+            }
+
+            let alternatives = if let Some(rest) = line.strip_prefix('|') {
+                match &left {
+                    Some(_) => rest,
+                    None => {
+                        errors.push(GrammarError::Syntax {
+                            line: n,
+                            message: String::from("alternativa '|' sem produção anterior"),
+                        });
+                        continue;
+                    }
+                }
+            } else if let Some((head, rest)) = line.split_once("->") {
+                match head.split_whitespace().collect::<Vec<&str>>()[..] {
+                    [head] => left = Some(String::from(head)),
+                    _ => {
+                        errors.push(GrammarError::Syntax {
+                            line: n,
+                            message: format!("lado esquerdo inválido '{}'", head.trim()),
+                        });
+                        left = None;
+                        continue;
+                    }
+                }
+                rest
+            } else if line.starts_with('%') {
+                errors.push(GrammarError::Syntax {
+                    line: n,
+                    message: format!("declaração desconhecida '{}'", line),
+                });
+                continue;
+            } else {
+                errors.push(GrammarError::Syntax {
+                    line: n,
+                    message: String::from("esperado 'A -> ...', '| ...' ou uma declaração"),
+                });
+                continue;
+            };
+
+            let head = left.clone().unwrap_or_default();
+            for alternative in alternatives.split('|') {
+                if alternative.trim().is_empty() {
+                    errors.push(GrammarError::Syntax {
+                        line: n,
+                        message: String::from("alternativa vazia, use 'ε' para a palavra vazia"),
+                    });
+                    continue;
+                }
+
+                let right: Vec<String> = alternative
+                    .split_whitespace()
+                    .filter(|symbol| *symbol != "ε")
+                    .map(String::from)
+                    .collect();
+                productions.push((n, head.clone(), right));
+            }
+        }
+
+        if productions.is_empty() {
+            errors.push(GrammarError::Empty);
+            return Err(errors);
+        }
+
+        let non_terminals: HashSet<&str> = productions
+            .iter()
+            .map(|(_, left, _)| left.as_str())
+            .collect();
+
+        let start = start.unwrap_or_else(|| productions[0].1.clone());
+        if !non_terminals.contains(start.as_str()) {
+            errors.push(GrammarError::UnknownStart(start.clone()));
+        }
+
+        let mut terminal_ids = Interner::new();
+        for token in &tokens {
+            terminal_ids.intern(token);
+        }
+        terminal_ids.intern(END_MARKER);
 
-        grammar
+        let augmented = format!("{}'", start);
+        let mut non_terminal_ids = Interner::new();
+        non_terminal_ids.intern(&augmented);
+        for (_, left, _) in &productions {
+            non_terminal_ids.intern(left);
+        }
+
+        let mut rules: Vec<GrammarRule> = vec![GrammarRule {
+            left: non_terminal(&augmented, &non_terminal_ids),
+            right: vec![non_terminal(&start, &non_terminal_ids)],
+        }];
+
+        for (line, left, right) in &productions {
+            if tokens.contains(left) {
+                errors.push(GrammarError::TerminalWithRules {
+                    line: *line,
+                    symbol: left.clone(),
+                });
+            }
+
+            let mut items: Vec<AlphabetItem> = Vec::new();
+            for symbol in right {
+                let item = match terminal_ids.id(symbol) {
+                    Some(id) => AlphabetItem {
+                        text: symbol.clone(),
+                        terminal: true,
+                        id,
+                    },
+                    None => {
+                        if !non_terminals.contains(symbol.as_str()) {
+                            errors.push(GrammarError::UndeclaredSymbol {
+                                line: *line,
+                                symbol: symbol.clone(),
+                            });
+                        }
+                        non_terminal(symbol, &non_terminal_ids)
+                    }
+                };
+                items.push(item);
+            }
+
+            rules.push(GrammarRule {
+                left: non_terminal(left, &non_terminal_ids),
+                right: items,
+            });
+        }
+
+        let grammar = Grammar {
+            rules,
+            terminals: terminal_ids,
+            non_terminals: non_terminal_ids,
+        };
+
+        if errors.is_empty() {
+            for symbol in grammar.non_productive() {
+                errors.push(GrammarError::NonProductive(symbol));
+            }
+            for symbol in grammar.unreachable() {
+                errors.push(GrammarError::Unreachable(symbol));
+            }
+        }
+
+        match errors.is_empty() {
+            true => Ok(grammar),
+            false => Err(errors),
+        }
     }
 
     pub fn get_rule(&self, index: usize) -> GrammarRule {
         self.rules[index].clone()
     }
 
+    pub fn rules(&self) -> &[GrammarRule] {
+        &self.rules
+    }
+
+    pub fn start(&self) -> &str {
+        &self.rules[0].left.text
+    }
+
+    pub fn terminals(&self) -> Vec<String> {
+        self.terminals.names().to_vec()
+    }
+
+    pub fn non_terminals(&self) -> Vec<String> {
+        self.non_terminals.names().to_vec()
+    }
+
+    pub fn terminal_id(&self, name: &str) -> Option<usize> {
+        self.terminals.id(name)
+    }
+
+    pub fn non_terminal_id(&self, name: &str) -> Option<usize> {
+        self.non_terminals.id(name)
+    }
+
     pub fn show(&self) {
         for n in 0..self.rules.len() {
             print!("{}.", n);
@@ -47,62 +338,60 @@ impl Grammar {
         }
     }
 
-    fn init_rules(&mut self) {
-        self.add_rule("P'", "P");
-        self.add_rule("P", "inicio V A");
-        self.add_rule("V", "varinicio LV");
-        self.add_rule("LV", "D LV");
-        self.add_rule("LV", "varfim pt_v");
-        self.add_rule("D", "TIPO L pt_v");
-        self.add_rule("L", "id vir L");
-        self.add_rule("L", "id");
-        self.add_rule("TIPO", "inteiro");
-        self.add_rule("TIPO", "real");
-        self.add_rule("TIPO", "literal");
-        self.add_rule("A", "ES A");
-        self.add_rule("ES", "leia id pt_v");
-        self.add_rule("ES", "escreva ARG pt_v");
-        self.add_rule("ARG", "lit");
-        self.add_rule("ARG", "num");
-        self.add_rule("ARG", "id");
-        self.add_rule("A", "CMD A");
-        self.add_rule("CMD", "id rcb LD pt_v");
-        self.add_rule("LD", "OPRD opm OPRD");
-        self.add_rule("LD", "OPRD");
-        self.add_rule("OPRD", "id");
-        self.add_rule("OPRD", "num");
-        self.add_rule("A", "COND A");
-        self.add_rule("COND", "CAB CP");
-        self.add_rule("CAB", "se ab_p EXP_R fc_p então");
-        self.add_rule("EXP_R", "OPRD opr OPRD");
-        self.add_rule("CP", "ES CP");
-        self.add_rule("CP", "CMD CP");
-        self.add_rule("CP", "COND CP");
-        self.add_rule("CP", "fimse");
-        self.add_rule("A", "R A");
-        self.add_rule("R", "CABR CPR");
-        self.add_rule("CABR", "repita ab_p EXP_R fc_p");
-        self.add_rule("CPR", "ES CPR");
-        self.add_rule("CPR", "CMD CPR");
-        self.add_rule("CPR", "COND CPR");
-        self.add_rule("CPR", "fimrepita");
-        self.add_rule("A", "fim");
-    }
-
-    fn add_rule(&mut self, left_str: &str, right_str: &str) {
-        let left = AlphabetItem {
-            text: String::from(left_str),
-            terminal: false,
-        };
+    fn non_productive(&self) -> Vec<String> {
+        let mut productive: HashSet<&str> = HashSet::new();
+        let mut changed = true;
 
-        let mut right: Vec<AlphabetItem> = Vec::new();
-        for item in right_str.split_whitespace() {
-            right.push(AlphabetItem {
-                text: String::from(item),
-                terminal: item.to_lowercase().eq(item),
-            });
+        while changed {
+            changed = false;
+            for rule in &self.rules {
+                if !productive.contains(rule.left.text.as_str())
+                    && rule
+                        .right
+                        .iter()
+                        .all(|item| item.terminal || productive.contains(item.text.as_str()))
+                {
+                    productive.insert(&rule.left.text);
+                    changed = true;
+                }
+            }
         }
 
-        self.rules.push(GrammarRule { left, right });
+        // The augmented start is only productive along with the start
+        // symbol, which is reported instead.
+        self.non_terminals()
+            .into_iter()
+            .filter(|symbol| symbol != self.start() && !productive.contains(symbol.as_str()))
+            .collect()
+    }
+
+    fn unreachable(&self) -> Vec<String> {
+        let mut reachable: Vec<&str> = vec![self.start()];
+
+        let mut i = 0;
+        while i < reachable.len() {
+            let symbol = reachable[i];
+            for rule in self.rules.iter().filter(|rule| rule.left.text == symbol) {
+                for item in rule.right.iter().filter(|item| !item.terminal) {
+                    if !reachable.contains(&item.text.as_str()) {
+                        reachable.push(&item.text);
+                    }
+                }
+            }
+            i += 1;
+        }
+
+        self.non_terminals()
+            .into_iter()
+            .filter(|symbol| symbol != self.start() && !reachable.contains(&symbol.as_str()))
+            .collect()
+    }
+}
+
+fn non_terminal(text: &str, ids: &Interner) -> AlphabetItem {
+    AlphabetItem {
+        text: String::from(text),
+        terminal: false,
+        id: ids.id(text).unwrap_or(0),
     }
 }
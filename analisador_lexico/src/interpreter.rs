@@ -0,0 +1,333 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    io::{BufRead, Write},
+};
+
+use crate::{
+    ast::{Argument, Command, Condition, Expression, Operand, Program},
+    token::Token,
+    token_type::TokenType,
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Inteiro(i64),
+    Real(f64),
+    Literal(String),
+}
+
+impl Value {
+    fn as_real(&self) -> f64 {
+        match self {
+            Value::Inteiro(n) => *n as f64,
+            Value::Real(n) => *n,
+            Value::Literal(_) => 0.0,
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Inteiro(n) => write!(f, "{}", n),
+            Value::Real(n) => write!(f, "{:.6}", n),
+            Value::Literal(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct RuntimeError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for RuntimeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Erro de execução na linha {}: {}",
+            self.line, self.message
+        )
+    }
+}
+
+pub struct Interpreter<R: BufRead, W: Write> {
+    input: R,
+    output: W,
+    variables: HashMap<String, Value>,
+}
+
+impl<R: BufRead, W: Write> Interpreter<R, W> {
+    pub fn new(input: R, output: W) -> Interpreter<R, W> {
+        Interpreter {
+            input,
+            output,
+            variables: HashMap::new(),
+        }
+    }
+
+    /// Where the program wrote, e.g. to inspect a `Vec<u8>` after `run`.
+    pub fn output(&self) -> &W {
+        &self.output
+    }
+
+    pub fn run(&mut self, program: &Program) -> Result<(), RuntimeError> {
+        self.variables.clear();
+
+        for declaration in &program.declarations {
+            let value = match declaration.data_type.token_type {
+                Some(TokenType::Literal) => Value::Literal(String::new()),
+                Some(TokenType::Real) => Value::Real(0.0),
+                _ => Value::Inteiro(0),
+            };
+
+            for id in &declaration.ids {
+                self.variables
+                    .entry(lexeme(id))
+                    .or_insert_with(|| value.clone());
+            }
+        }
+
+        self.commands(&program.commands)?;
+
+        self.output.flush().map_err(|err| RuntimeError {
+            line: 0,
+            message: format!("não foi possível escrever a saída ({})", err),
+        })
+    }
+
+    fn commands(&mut self, commands: &[Command]) -> Result<(), RuntimeError> {
+        for command in commands {
+            self.command(command)?;
+        }
+
+        Ok(())
+    }
+
+    fn command(&mut self, command: &Command) -> Result<(), RuntimeError> {
+        match command {
+            Command::Read { id, .. } => {
+                let mut line = String::new();
+                match self.input.read_line(&mut line) {
+                    Ok(0) => {
+                        return Err(error(id, String::from("fim da entrada durante um 'leia'")))
+                    }
+                    Ok(_) => (),
+                    Err(err) => {
+                        return Err(error(
+                            id,
+                            format!("não foi possível ler a entrada ({})", err),
+                        ))
+                    }
+                }
+                let text = line.trim_end_matches(['\n', '\r']);
+
+                let value = match self.get(id)? {
+                    Value::Inteiro(_) => match text.trim().parse::<i64>() {
+                        Ok(n) => Value::Inteiro(n),
+                        Err(_) => {
+                            return Err(error(
+                                id,
+                                format!(
+                                    "'{}' não é um número inteiro válido para '{}'",
+                                    text,
+                                    lexeme(id)
+                                ),
+                            ))
+                        }
+                    },
+                    Value::Real(_) => match text.trim().parse::<f64>() {
+                        Ok(n) => Value::Real(n),
+                        Err(_) => {
+                            return Err(error(
+                                id,
+                                format!(
+                                    "'{}' não é um número real válido para '{}'",
+                                    text,
+                                    lexeme(id)
+                                ),
+                            ))
+                        }
+                    },
+                    Value::Literal(_) => Value::Literal(String::from(text)),
+                };
+
+                self.variables.insert(lexeme(id), value);
+            }
+            Command::Write { keyword, argument } => {
+                let text = match argument {
+                    Argument::Literal(lit) => unescape(&lexeme(lit)),
+                    Argument::Number(num) => number(num)?.to_string(),
+                    Argument::Id(id) => self.get(id)?.to_string(),
+                };
+
+                write!(self.output, "{}", text).map_err(|err| {
+                    error(
+                        keyword,
+                        format!("não foi possível escrever a saída ({})", err),
+                    )
+                })?;
+            }
+            Command::Assign { id, value, .. } => {
+                let value = self.expression(value)?;
+                let value = match (self.get(id)?, value) {
+                    (Value::Inteiro(_), Value::Real(n)) => Value::Inteiro(n as i64),
+                    (Value::Real(_), Value::Inteiro(n)) => Value::Real(n as f64),
+                    (_, value) => value,
+                };
+
+                self.variables.insert(lexeme(id), value);
+            }
+            Command::If {
+                condition, body, ..
+            } => {
+                if self.condition(condition)? {
+                    self.commands(body)?;
+                }
+            }
+            Command::Repeat {
+                condition, body, ..
+            } => {
+                while self.condition(condition)? {
+                    self.commands(body)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn expression(&self, expression: &Expression) -> Result<Value, RuntimeError> {
+        match expression {
+            Expression::Operand(operand) => self.operand(operand),
+            Expression::Arithmetic {
+                left,
+                operator,
+                right,
+            } => {
+                let left = self.operand(left)?;
+                let right = self.operand(right)?;
+                let op = lexeme(operator);
+
+                match (&left, &right) {
+                    (Value::Inteiro(a), Value::Inteiro(b)) => match op.as_str() {
+                        "+" => Ok(Value::Inteiro(a.wrapping_add(*b))),
+                        "-" => Ok(Value::Inteiro(a.wrapping_sub(*b))),
+                        "*" => Ok(Value::Inteiro(a.wrapping_mul(*b))),
+                        _ if *b == 0 => Err(error(operator, String::from("divisão por zero"))),
+                        _ => Ok(Value::Inteiro(a.wrapping_div(*b))),
+                    },
+                    (Value::Literal(_), _) | (_, Value::Literal(_)) => Err(error(
+                        operator,
+                        format!("o operador '{}' não pode ser aplicado a um literal", op),
+                    )),
+                    _ => {
+                        let (a, b) = (left.as_real(), right.as_real());
+                        match op.as_str() {
+                            "+" => Ok(Value::Real(a + b)),
+                            "-" => Ok(Value::Real(a - b)),
+                            "*" => Ok(Value::Real(a * b)),
+                            _ => Ok(Value::Real(a / b)),
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn condition(&self, condition: &Condition) -> Result<bool, RuntimeError> {
+        let left = self.operand(&condition.left)?;
+        let right = self.operand(&condition.right)?;
+
+        if let (Value::Literal(a), Value::Literal(b)) = (&left, &right) {
+            return Ok(compare(a.cmp(b), &lexeme(&condition.operator)));
+        }
+
+        match left.as_real().partial_cmp(&right.as_real()) {
+            Some(ordering) => Ok(compare(ordering, &lexeme(&condition.operator))),
+            None => Ok(lexeme(&condition.operator) == "<>"),
+        }
+    }
+
+    fn operand(&self, operand: &Operand) -> Result<Value, RuntimeError> {
+        match operand {
+            Operand::Id(id) => self.get(id),
+            Operand::Number(num) => number(num),
+        }
+    }
+
+    fn get(&self, id: &Token) -> Result<Value, RuntimeError> {
+        match self.variables.get(&lexeme(id)) {
+            Some(value) => Ok(value.clone()),
+            None => Err(error(
+                id,
+                format!("a variável '{}' não foi declarada", lexeme(id)),
+            )),
+        }
+    }
+}
+
+fn compare(ordering: std::cmp::Ordering, operator: &str) -> bool {
+    match operator {
+        "<" => ordering.is_lt(),
+        ">" => ordering.is_gt(),
+        "<=" => ordering.is_le(),
+        ">=" => ordering.is_ge(),
+        "<>" => ordering.is_ne(),
+        _ => ordering.is_eq(),
+    }
+}
+
+fn number(token: &Token) -> Result<Value, RuntimeError> {
+    let text = lexeme(token);
+    if let (Some(TokenType::Inteiro), Ok(n)) = (&token.token_type, text.parse::<i64>()) {
+        return Ok(Value::Inteiro(n));
+    }
+
+    let value = text
+        .parse::<f64>()
+        .map_err(|_| error(token, format!("'{}' não é um número válido", text)))?;
+
+    match token.token_type {
+        Some(TokenType::Real) => Ok(Value::Real(value)),
+        _ => Ok(Value::Inteiro(value as i64)),
+    }
+}
+
+fn unescape(lit: &str) -> String {
+    let text = lit
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .unwrap_or(lit);
+
+    let mut result = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some(other) => result.push(other),
+            None => result.push('\\'),
+        }
+    }
+
+    result
+}
+
+fn lexeme(token: &Token) -> String {
+    token.lexeme.clone().unwrap_or_default()
+}
+
+fn error(token: &Token, message: String) -> RuntimeError {
+    RuntimeError {
+        line: token.span.line,
+        message,
+    }
+}
@@ -0,0 +1,422 @@
+use std::fmt;
+
+use crate::{
+    catalog::Locale,
+    diagnostic::{Code, Diagnostic, Phase, Severity},
+    span::Span,
+};
+
+/// Quotes `text` as a JSON string, escaping what RFC 8259 requires.
+pub fn string(text: &str) -> String {
+    let mut json = String::with_capacity(text.len() + 2);
+    json.push('"');
+
+    for c in text.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            '\r' => json.push_str("\\r"),
+            '\t' => json.push_str("\\t"),
+            c if (c as u32) < 0x20 => json.push_str(&format!("\\u{:04x}", c as u32)),
+            c => json.push(c),
+        }
+    }
+
+    json.push('"');
+    json
+}
+
+/// The diagnostics as one JSON object, with messages in `locale`:
+///
+/// ```text
+/// {"erros":1,"diagnosticos":[{"fase":"sintatico","codigo":"ES2",
+///  "severidade":"error","mensagem":"ausência de ';'","linha":5,"coluna":7,
+///  ...,"rotulos":[],"notas":[],"correcoes":[...]}]}
+/// ```
+pub fn diagnostics(diagnostics: &[&Diagnostic], locale: Locale) -> String {
+    let errors = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.severity == Severity::Error)
+        .count();
+    let diagnostics: Vec<String> = diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic_object(diagnostic, locale))
+        .collect();
+
+    format!("{{\"erros\":{},\"diagnosticos\":[{}]}}", errors, diagnostics.join(","))
+}
+
+fn diagnostic_object(diagnostic: &Diagnostic, locale: Locale) -> String {
+    let labels: Vec<String> = diagnostic
+        .labels
+        .iter()
+        .map(|label| {
+            format!(
+                "{{\"mensagem\":{},{}}}",
+                string(&label.message.render(locale)),
+                span_fields(label.span)
+            )
+        })
+        .collect();
+    let notes: Vec<String> = diagnostic
+        .notes
+        .iter()
+        .map(|note| string(&note.render(locale)))
+        .collect();
+    let fixes: Vec<String> = diagnostic
+        .fixes
+        .iter()
+        .map(|fix| {
+            format!(
+                "{{\"mensagem\":{},\"substituicao\":{},{}}}",
+                string(&fix.message.render(locale)),
+                string(&fix.replacement),
+                span_fields(fix.span)
+            )
+        })
+        .collect();
+
+    format!(
+        "{{\"fase\":{},\"codigo\":{},\"severidade\":{},\"mensagem\":{},{},\"rotulos\":[{}],\"notas\":[{}],\"correcoes\":[{}]}}",
+        string(&Phase::to_str(diagnostic.phase())),
+        string(&Code::to_str(diagnostic.code)),
+        string(&Severity::to_str(diagnostic.severity)),
+        string(&diagnostic.message(locale)),
+        span_fields(diagnostic.span),
+        labels.join(","),
+        notes.join(","),
+        fixes.join(",")
+    )
+}
+
+/// The same position fields as the token dump.
+fn span_fields(span: Span) -> String {
+    format!(
+        "\"linha\":{},\"coluna\":{},\"linha_fim\":{},\"coluna_fim\":{},\"inicio\":{},\"fim\":{}",
+        span.line, span.column, span.end_line, span.end_column, span.start, span.end
+    )
+}
+
+/// A parsed JSON document, for the protocols that read JSON, such as the
+/// language server. Objects keep their keys in order.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Null,
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    pub fn parse(text: &str) -> Result<Value, String> {
+        let mut parser = ValueParser {
+            chars: text.chars().collect(),
+            position: 0,
+            depth: 0,
+        };
+
+        let value = parser.value()?;
+        parser.whitespace();
+        match parser.position == parser.chars.len() {
+            true => Ok(value),
+            false => Err(format!("caractere inesperado na posição {}", parser.position)),
+        }
+    }
+
+    pub fn object(fields: Vec<(&str, Value)>) -> Value {
+        Value::Object(
+            fields
+                .into_iter()
+                .map(|(key, value)| (String::from(key), value))
+                .collect(),
+        )
+    }
+
+    /// The field `key` of an object.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(fields) => fields
+                .iter()
+                .find(|(name, _)| name == key)
+                .map(|(_, value)| value),
+            _ => None,
+        }
+    }
+
+    /// Follows `keys` through nested objects.
+    pub fn at(&self, keys: &[&str]) -> Option<&Value> {
+        keys.iter().try_fold(self, |value, key| value.get(key))
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    pub fn as_usize(&self) -> Option<usize> {
+        match self {
+            Value::Number(number) if *number >= 0.0 && number.fract() == 0.0 => Some(*number as usize),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Value]> {
+        match self {
+            Value::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+}
+
+impl From<&str> for Value {
+    fn from(text: &str) -> Value {
+        Value::String(String::from(text))
+    }
+}
+
+impl From<String> for Value {
+    fn from(text: String) -> Value {
+        Value::String(text)
+    }
+}
+
+impl From<usize> for Value {
+    fn from(number: usize) -> Value {
+        Value::Number(number as f64)
+    }
+}
+
+impl From<bool> for Value {
+    fn from(value: bool) -> Value {
+        Value::Bool(value)
+    }
+}
+
+/// Compact JSON, with integral numbers written without a fraction.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Null => write!(f, "null"),
+            Value::Bool(value) => write!(f, "{}", value),
+            Value::Number(number) if number.fract() == 0.0 && number.abs() < 1e15 => {
+                write!(f, "{}", *number as i64)
+            }
+            Value::Number(number) if number.is_finite() => write!(f, "{}", number),
+            Value::Number(_) => write!(f, "null"),
+            Value::String(text) => write!(f, "{}", string(text)),
+            Value::Array(values) => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            }
+            Value::Object(fields) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}:{}", string(key), value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+/// How deeply arrays and objects may nest. The parser is recursive, so
+/// without a limit a document such as `[[[[...` would overflow the stack.
+const MAX_DEPTH: usize = 128;
+
+struct ValueParser {
+    chars: Vec<char>,
+    position: usize,
+    depth: usize,
+}
+
+impl ValueParser {
+    fn value(&mut self) -> Result<Value, String> {
+        self.whitespace();
+
+        match self.peek() {
+            Some(c) if c == '{' || c == '[' => {
+                if self.depth == MAX_DEPTH {
+                    return Err(format!(
+                        "mais de {} níveis de aninhamento na posição {}",
+                        MAX_DEPTH, self.position
+                    ));
+                }
+
+                self.depth += 1;
+                let value = match c {
+                    '{' => self.object(),
+                    _ => self.array(),
+                };
+                self.depth -= 1;
+                value
+            }
+            Some('"') => Ok(Value::String(self.string()?)),
+            Some('t') => self.keyword("true", Value::Bool(true)),
+            Some('f') => self.keyword("false", Value::Bool(false)),
+            Some('n') => self.keyword("null", Value::Null),
+            Some(c) if c == '-' || c.is_ascii_digit() => self.number(),
+            Some(c) => Err(format!("caractere inesperado {:?} na posição {}", c, self.position)),
+            None => Err(String::from("fim inesperado do JSON")),
+        }
+    }
+
+    fn object(&mut self) -> Result<Value, String> {
+        let mut fields: Vec<(String, Value)> = Vec::new();
+        self.expect('{')?;
+
+        self.whitespace();
+        if self.peek() == Some('}') {
+            self.position += 1;
+            return Ok(Value::Object(fields));
+        }
+
+        loop {
+            self.whitespace();
+            let key = self.string()?;
+            self.whitespace();
+            self.expect(':')?;
+            fields.push((key, self.value()?));
+
+            self.whitespace();
+            match self.bump() {
+                Some(',') => continue,
+                Some('}') => return Ok(Value::Object(fields)),
+                _ => return Err(format!("esperado ',' ou '}}' na posição {}", self.position)),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Value, String> {
+        let mut values: Vec<Value> = Vec::new();
+        self.expect('[')?;
+
+        self.whitespace();
+        if self.peek() == Some(']') {
+            self.position += 1;
+            return Ok(Value::Array(values));
+        }
+
+        loop {
+            values.push(self.value()?);
+
+            self.whitespace();
+            match self.bump() {
+                Some(',') => continue,
+                Some(']') => return Ok(Value::Array(values)),
+                _ => return Err(format!("esperado ',' ou ']' na posição {}", self.position)),
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        let mut text = String::new();
+        self.expect('"')?;
+
+        loop {
+            match self.bump() {
+                Some('"') => return Ok(text),
+                Some('\\') => match self.bump() {
+                    Some('"') => text.push('"'),
+                    Some('\\') => text.push('\\'),
+                    Some('/') => text.push('/'),
+                    Some('b') => text.push('\u{8}'),
+                    Some('f') => text.push('\u{c}'),
+                    Some('n') => text.push('\n'),
+                    Some('r') => text.push('\r'),
+                    Some('t') => text.push('\t'),
+                    Some('u') => {
+                        let high = self.hex()?;
+                        let code = match (0xd800..0xdc00).contains(&high) {
+                            true => {
+                                self.expect('\\')?;
+                                self.expect('u')?;
+                                let low = self.hex()?;
+                                0x10000 + ((high - 0xd800) << 10) + (low.wrapping_sub(0xdc00) & 0x3ff)
+                            }
+                            false => high,
+                        };
+                        text.push(char::from_u32(code).unwrap_or(char::REPLACEMENT_CHARACTER));
+                    }
+                    _ => return Err(format!("escape inválido na posição {}", self.position)),
+                },
+                Some(c) => text.push(c),
+                None => return Err(String::from("string não terminada")),
+            }
+        }
+    }
+
+    fn hex(&mut self) -> Result<u32, String> {
+        let mut code = 0;
+        for _ in 0..4 {
+            match self.bump().and_then(|c| c.to_digit(16)) {
+                Some(digit) => code = code * 16 + digit,
+                None => return Err(format!("escape \\u inválido na posição {}", self.position)),
+            }
+        }
+
+        Ok(code)
+    }
+
+    fn number(&mut self) -> Result<Value, String> {
+        let start = self.position;
+        while let Some(c) = self.peek() {
+            match c {
+                '0'..='9' | '-' | '+' | '.' | 'e' | 'E' => self.position += 1,
+                _ => break,
+            }
+        }
+
+        let text: String = self.chars[start..self.position].iter().collect();
+        match text.parse::<f64>() {
+            Ok(number) => Ok(Value::Number(number)),
+            Err(_) => Err(format!("número inválido '{}'", text)),
+        }
+    }
+
+    fn keyword(&mut self, keyword: &str, value: Value) -> Result<Value, String> {
+        for expected in keyword.chars() {
+            if self.bump() != Some(expected) {
+                return Err(format!("esperado '{}' na posição {}", keyword, self.position));
+            }
+        }
+
+        Ok(value)
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        match self.bump() {
+            Some(c) if c == expected => Ok(()),
+            _ => Err(format!("esperado '{}' na posição {}", expected, self.position)),
+        }
+    }
+
+    fn whitespace(&mut self) {
+        while matches!(self.peek(), Some(' ' | '\t' | '\n' | '\r')) {
+            self.position += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.position += 1;
+        c
+    }
+}
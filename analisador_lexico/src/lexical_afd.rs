@@ -20,6 +20,7 @@ pub struct AFD {
 }
 
 impl AFD {
+    #[allow(clippy::new_without_default)]
     pub fn new() -> AFD {
         AFD {
             state: AFDState::Initial,
@@ -172,6 +173,7 @@ impl AFD {
     }
 }
 
+#[allow(clippy::match_like_matches_macro)]
 fn is_valid(c: char) -> bool {
     match c {
         '0'..='9' => true,
@@ -3,7 +3,28 @@ pub mod symbol_table;
 pub mod token_type;
 pub mod class;
 pub mod token;
+pub mod span;
+pub mod diagnostic;
+pub mod catalog;
+pub mod render;
 pub mod lexical_afd;
 pub mod grammar;
+pub mod interner;
 pub mod parser;
-pub mod slr_table;
\ No newline at end of file
+pub mod slr_table;
+pub mod ast;
+pub mod semantic;
+pub mod codegen;
+pub mod interpreter;
+pub mod first_follow;
+pub mod lr0;
+pub mod lr1;
+pub mod table_builder;
+pub mod embedded_tables;
+pub mod json;
+pub mod sarif;
+pub mod lsp;
+pub mod token_dump;
+pub mod trace;
+pub mod dot;
+pub mod parse_tree;
\ No newline at end of file
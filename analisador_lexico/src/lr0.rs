@@ -0,0 +1,185 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{
+    dot,
+    grammar::{AlphabetItem, Grammar},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Item {
+    pub rule: usize,
+    pub dot: usize,
+}
+
+impl Item {
+    pub fn next_symbol<'a>(&self, grammar: &'a Grammar) -> Option<&'a AlphabetItem> {
+        grammar.rules()[self.rule].right.get(self.dot)
+    }
+
+    pub fn is_complete(&self, grammar: &Grammar) -> bool {
+        self.dot >= grammar.rules()[self.rule].right.len()
+    }
+
+    pub fn show(&self, grammar: &Grammar) -> String {
+        let rule = &grammar.rules()[self.rule];
+        let mut right: Vec<&str> = rule.right.iter().map(|item| item.text.as_str()).collect();
+        right.insert(self.dot, "•");
+
+        format!("{} -> {}", rule.left.text, right.join(" "))
+    }
+}
+
+pub struct LR0Automaton {
+    pub states: Vec<Vec<Item>>,
+    pub transitions: Vec<BTreeMap<String, usize>>,
+}
+
+impl LR0Automaton {
+    pub fn new(grammar: &Grammar) -> LR0Automaton {
+        let mut automaton = LR0Automaton {
+            states: Vec::new(),
+            transitions: Vec::new(),
+        };
+        let mut kernels: HashMap<Vec<Item>, usize> = HashMap::new();
+
+        let start = vec![Item { rule: 0, dot: 0 }];
+        kernels.insert(start.clone(), 0);
+        automaton.states.push(closure(grammar, &start));
+        automaton.transitions.push(BTreeMap::new());
+
+        let mut i = 0;
+        while i < automaton.states.len() {
+            for (symbol, kernel) in successors(grammar, &automaton.states[i]) {
+                let target = match kernels.get(&kernel) {
+                    Some(&target) => target,
+                    None => {
+                        let target = automaton.states.len();
+                        kernels.insert(kernel.clone(), target);
+                        automaton.states.push(closure(grammar, &kernel));
+                        automaton.transitions.push(BTreeMap::new());
+                        target
+                    }
+                };
+                automaton.transitions[i].insert(symbol, target);
+            }
+            i += 1;
+        }
+
+        automaton
+    }
+
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    pub fn kernel(&self, state: usize) -> Vec<Item> {
+        self.states[state]
+            .iter()
+            .filter(|item| item.dot > 0 || item.rule == 0)
+            .cloned()
+            .collect()
+    }
+
+    /// The canonical collection as a Graphviz state diagram: every state
+    /// with its items, kernel first, and the transitions labelled by symbol,
+    /// dashed for nonterminals. The accepting state has a double border.
+    pub fn to_dot(&self, grammar: &Grammar) -> String {
+        let mut text = String::from(
+            "digraph lr0 {\n    rankdir=LR;\n    node [shape=box, fontname=\"monospace\"];\n",
+        );
+
+        for (state, items) in self.states.iter().enumerate() {
+            let mut label = format!("I{}\\l", state);
+            for item in items {
+                label.push_str(&dot::escape(&item.show(grammar)));
+                label.push_str("\\l");
+            }
+            let accepting = items.contains(&Item { rule: 0, dot: 1 });
+
+            text.push_str(&format!(
+                "    s{} [label=\"{}\"{}];\n",
+                state,
+                label,
+                match accepting {
+                    true => ", peripheries=2",
+                    false => "",
+                }
+            ));
+        }
+
+        for (state, transitions) in self.transitions.iter().enumerate() {
+            for (symbol, target) in transitions {
+                let style = match grammar.terminal_id(symbol) {
+                    Some(_) => "",
+                    None => ", style=dashed",
+                };
+                text.push_str(&format!(
+                    "    s{} -> s{} [label={}{}];\n",
+                    state,
+                    target,
+                    dot::string(symbol),
+                    style
+                ));
+            }
+        }
+
+        text.push_str("}\n");
+        text
+    }
+}
+
+pub fn closure(grammar: &Grammar, kernel: &[Item]) -> Vec<Item> {
+    let mut items: Vec<Item> = kernel.to_vec();
+
+    let mut i = 0;
+    while i < items.len() {
+        if let Some(symbol) = items[i].next_symbol(grammar) {
+            if !symbol.terminal {
+                for (rule, _) in grammar
+                    .rules()
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, rule)| rule.left.text == symbol.text)
+                {
+                    let item = Item { rule, dot: 0 };
+                    if !items.contains(&item) {
+                        items.push(item);
+                    }
+                }
+            }
+        }
+        i += 1;
+    }
+
+    items
+}
+
+/// Kernels reachable from `items`, one per symbol after a dot, in the order the
+/// symbols first appear in the closure so state numbers follow the grammar.
+pub fn successors(grammar: &Grammar, items: &[Item]) -> Vec<(String, Vec<Item>)> {
+    let mut successors: Vec<(String, Vec<Item>)> = Vec::new();
+
+    for item in items {
+        if let Some(symbol) = item.next_symbol(grammar) {
+            let advanced = Item {
+                rule: item.rule,
+                dot: item.dot + 1,
+            };
+
+            match successors.iter_mut().find(|(s, _)| *s == symbol.text) {
+                Some((_, kernel)) => kernel.push(advanced),
+                None => successors.push((symbol.text.clone(), vec![advanced])),
+            }
+        }
+    }
+
+    for (_, kernel) in successors.iter_mut() {
+        kernel.sort();
+    }
+
+    successors
+}
@@ -0,0 +1,178 @@
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use crate::{
+    first_follow::FirstFollow,
+    grammar::{Grammar, END_MARKER},
+    lr0::{Item, LR0Automaton},
+};
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LR1Item {
+    pub item: Item,
+    pub lookahead: String,
+}
+
+pub struct LR1Automaton {
+    pub states: Vec<Vec<LR1Item>>,
+    pub transitions: Vec<BTreeMap<String, usize>>,
+}
+
+impl LR1Automaton {
+    pub fn new(grammar: &Grammar, sets: &FirstFollow) -> LR1Automaton {
+        let mut automaton = LR1Automaton {
+            states: Vec::new(),
+            transitions: Vec::new(),
+        };
+        let mut kernels: HashMap<Vec<LR1Item>, usize> = HashMap::new();
+
+        let start = vec![LR1Item {
+            item: Item { rule: 0, dot: 0 },
+            lookahead: String::from(END_MARKER),
+        }];
+        kernels.insert(start.clone(), 0);
+        automaton.states.push(closure(grammar, sets, &start));
+        automaton.transitions.push(BTreeMap::new());
+
+        let mut i = 0;
+        while i < automaton.states.len() {
+            for (symbol, kernel) in successors(grammar, &automaton.states[i]) {
+                let target = match kernels.get(&kernel) {
+                    Some(&target) => target,
+                    None => {
+                        let target = automaton.states.len();
+                        kernels.insert(kernel.clone(), target);
+                        automaton.states.push(closure(grammar, sets, &kernel));
+                        automaton.transitions.push(BTreeMap::new());
+                        target
+                    }
+                };
+                automaton.transitions[i].insert(symbol, target);
+            }
+            i += 1;
+        }
+
+        automaton
+    }
+
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    /// The LR(0) items of a state, without repetitions for each lookahead.
+    pub fn core(&self, state: usize) -> Vec<Item> {
+        let mut core: Vec<Item> = Vec::new();
+        for lr1_item in &self.states[state] {
+            if !core.contains(&lr1_item.item) {
+                core.push(lr1_item.item);
+            }
+        }
+
+        core
+    }
+
+    pub fn lookaheads(&self, state: usize, item: &Item) -> BTreeSet<String> {
+        self.states[state]
+            .iter()
+            .filter(|lr1_item| lr1_item.item == *item)
+            .map(|lr1_item| lr1_item.lookahead.clone())
+            .collect()
+    }
+
+    /// Merges states with the same core onto the states of the LR(0)
+    /// automaton, which is how LALR(1) lookaheads are obtained.
+    pub fn merge_cores(&self, lr0: &LR0Automaton) -> HashMap<(usize, Item), BTreeSet<String>> {
+        let mut by_core: HashMap<Vec<Item>, usize> = HashMap::new();
+        for (state, items) in lr0.states.iter().enumerate() {
+            let mut core = items.clone();
+            core.sort();
+            by_core.insert(core, state);
+        }
+
+        let mut lookaheads: HashMap<(usize, Item), BTreeSet<String>> = HashMap::new();
+        for state in 0..self.states.len() {
+            let mut core = self.core(state);
+            core.sort();
+            let lr0_state = by_core[&core];
+
+            for lr1_item in &self.states[state] {
+                lookaheads
+                    .entry((lr0_state, lr1_item.item))
+                    .or_default()
+                    .insert(lr1_item.lookahead.clone());
+            }
+        }
+
+        lookaheads
+    }
+}
+
+pub fn closure(grammar: &Grammar, sets: &FirstFollow, kernel: &[LR1Item]) -> Vec<LR1Item> {
+    let mut items: Vec<LR1Item> = kernel.to_vec();
+
+    let mut i = 0;
+    while i < items.len() {
+        let LR1Item { item, lookahead } = items[i].clone();
+        i += 1;
+
+        let symbol = match item.next_symbol(grammar) {
+            Some(symbol) if !symbol.terminal => symbol,
+            _ => continue,
+        };
+
+        let rest = &grammar.rules()[item.rule].right[item.dot + 1..];
+        let (mut first, nullable) = sets.first_of(rest);
+        if nullable {
+            first.insert(lookahead);
+        }
+
+        for (rule, _) in grammar
+            .rules()
+            .iter()
+            .enumerate()
+            .filter(|(_, rule)| rule.left.text == symbol.text)
+        {
+            for terminal in &first {
+                let new_item = LR1Item {
+                    item: Item { rule, dot: 0 },
+                    lookahead: terminal.clone(),
+                };
+                if !items.contains(&new_item) {
+                    items.push(new_item);
+                }
+            }
+        }
+    }
+
+    items
+}
+
+pub fn successors(grammar: &Grammar, items: &[LR1Item]) -> Vec<(String, Vec<LR1Item>)> {
+    let mut successors: Vec<(String, Vec<LR1Item>)> = Vec::new();
+
+    for lr1_item in items {
+        if let Some(symbol) = lr1_item.item.next_symbol(grammar) {
+            let advanced = LR1Item {
+                item: Item {
+                    rule: lr1_item.item.rule,
+                    dot: lr1_item.item.dot + 1,
+                },
+                lookahead: lr1_item.lookahead.clone(),
+            };
+
+            match successors.iter_mut().find(|(s, _)| *s == symbol.text) {
+                Some((_, kernel)) => kernel.push(advanced),
+                None => successors.push((symbol.text.clone(), vec![advanced])),
+            }
+        }
+    }
+
+    for (_, kernel) in successors.iter_mut() {
+        kernel.sort();
+    }
+
+    successors
+}
@@ -0,0 +1,548 @@
+use std::{
+    collections::HashMap,
+    io::{self, BufRead, Read, Write},
+};
+
+use crate::{
+    catalog::Locale,
+    class::Class,
+    diagnostic::{Code, Diagnostics, Severity},
+    json::Value,
+    parser::Parser,
+    scanner::Scanner,
+    span::Span,
+    symbol_table::{SymbolTable, RESERVED_WORDS},
+    token::Token,
+    token_type::TokenType,
+};
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// The longest message body read. Mgol programs are small; a larger
+/// `Content-Length` is refused rather than allocated.
+pub const MAX_MESSAGE_LENGTH: usize = 4 * 1024 * 1024;
+
+// Kinds from the specification.
+const SYMBOL_VARIABLE: usize = 13;
+const COMPLETION_VARIABLE: usize = 6;
+const COMPLETION_KEYWORD: usize = 14;
+
+/// A language server for Mgol. `handle` takes one JSON-RPC message and
+/// returns the messages to send back, so the server can be driven in
+/// process; `serve` does the same over a stream with the `Content-Length`
+/// framing of the protocol.
+///
+/// Documents are analysed again on every change and every request: Mgol
+/// programs are small, so nothing is cached but their text.
+pub struct Server {
+    documents: HashMap<String, String>,
+    locale: Locale,
+    /// Whether positions count code points (`utf-32`) instead of UTF-16
+    /// code units, the default of the protocol.
+    utf32: bool,
+    shutdown: bool,
+    exited: bool,
+}
+
+/// What the server knows about a document: its diagnostics, the symbol
+/// table after parsing it, which records the declarations, and its tokens.
+struct Analysis {
+    diagnostics: Diagnostics,
+    symbol_table: SymbolTable,
+    tokens: Vec<Token>,
+}
+
+impl Default for Server {
+    fn default() -> Server {
+        Server::new()
+    }
+}
+
+impl Server {
+    pub fn new() -> Server {
+        Server {
+            documents: HashMap::new(),
+            locale: Locale::from_env().unwrap_or_default(),
+            utf32: false,
+            shutdown: false,
+            exited: false,
+        }
+    }
+
+    /// Whether the client has sent `exit`.
+    pub fn exited(&self) -> bool {
+        self.exited
+    }
+
+    /// The exit status the specification asks for: 0 when `shutdown` came
+    /// before `exit`, 1 otherwise.
+    pub fn exit_code(&self) -> i32 {
+        match self.shutdown {
+            true => 0,
+            false => 1,
+        }
+    }
+
+    pub fn handle(&mut self, message: &str) -> Vec<String> {
+        let message = match Value::parse(message) {
+            Ok(message) => message,
+            Err(error) => return vec![error_response(&Value::Null, PARSE_ERROR, &error).to_string()],
+        };
+
+        let method = message.get("method").and_then(Value::as_str).unwrap_or("");
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+        let id = message.get("id");
+
+        let outgoing: Vec<Value> = match id {
+            // A response to a request of the server, which sends none.
+            Some(_) if method.is_empty() => Vec::new(),
+            Some(id) if self.shutdown => vec![error_response(id, INVALID_REQUEST, "o servidor foi encerrado")],
+            Some(id) => vec![match self.request(method, &params) {
+                Ok(result) => Value::object(vec![
+                    ("jsonrpc", Value::from("2.0")),
+                    ("id", id.clone()),
+                    ("result", result),
+                ]),
+                Err((code, error)) => error_response(id, code, &error),
+            }],
+            None => self.notification(method, &params),
+        };
+
+        outgoing.iter().map(Value::to_string).collect()
+    }
+
+    fn request(&mut self, method: &str, params: &Value) -> Result<Value, (i64, String)> {
+        match method {
+            "initialize" => Ok(self.initialize(params)),
+            "shutdown" => {
+                self.shutdown = true;
+                Ok(Value::Null)
+            }
+            "textDocument/hover" => self.at_position(params, |server, uri, analysis, token| {
+                server.hover(uri, analysis, token)
+            }),
+            "textDocument/definition" => self.at_position(params, |server, uri, analysis, token| {
+                server.definition(uri, analysis, token)
+            }),
+            "textDocument/documentSymbol" => {
+                let (_, text) = self.document(params)?;
+                Ok(self.document_symbols(&text, &analyze(&text)))
+            }
+            "textDocument/completion" => {
+                let (_, text) = self.document(params)?;
+                Ok(completions(&analyze(&text)))
+            }
+            _ => Err((METHOD_NOT_FOUND, format!("método desconhecido '{}'", method))),
+        }
+    }
+
+    fn notification(&mut self, method: &str, params: &Value) -> Vec<Value> {
+        let uri = params
+            .at(&["textDocument", "uri"])
+            .and_then(Value::as_str)
+            .map(String::from);
+
+        match (method, uri) {
+            ("textDocument/didOpen", Some(uri)) => {
+                let text = params.at(&["textDocument", "text"]).and_then(Value::as_str).unwrap_or("");
+                self.documents.insert(uri.clone(), String::from(text));
+                vec![self.publish_diagnostics(&uri)]
+            }
+            ("textDocument/didChange", Some(uri)) => {
+                // Full synchronization: the last change is the whole text.
+                let text = params
+                    .get("contentChanges")
+                    .and_then(Value::as_array)
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change.get("text"))
+                    .and_then(Value::as_str);
+                if let Some(text) = text {
+                    self.documents.insert(uri.clone(), String::from(text));
+                }
+                vec![self.publish_diagnostics(&uri)]
+            }
+            ("textDocument/didClose", Some(uri)) => {
+                self.documents.remove(&uri);
+                vec![notification(
+                    "textDocument/publishDiagnostics",
+                    Value::object(vec![("uri", Value::from(uri)), ("diagnostics", Value::Array(Vec::new()))]),
+                )]
+            }
+            ("exit", _) => {
+                self.exited = true;
+                Vec::new()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn initialize(&mut self, params: &Value) -> Value {
+        if let Some(locale) = params.get("locale").and_then(Value::as_str).and_then(Locale::from_name) {
+            self.locale = locale;
+        }
+
+        let encodings = params
+            .at(&["capabilities", "general", "positionEncodings"])
+            .and_then(Value::as_array)
+            .unwrap_or(&[]);
+        self.utf32 = encodings.iter().any(|encoding| encoding.as_str() == Some("utf-32"));
+
+        let mut capabilities = vec![
+            ("textDocumentSync", Value::from(1)),
+            ("hoverProvider", Value::from(true)),
+            ("definitionProvider", Value::from(true)),
+            ("documentSymbolProvider", Value::from(true)),
+            ("completionProvider", Value::object(Vec::new())),
+        ];
+        if self.utf32 {
+            capabilities.push(("positionEncoding", Value::from("utf-32")));
+        }
+
+        Value::object(vec![
+            ("capabilities", Value::object(capabilities)),
+            (
+                "serverInfo",
+                Value::object(vec![
+                    ("name", Value::from("mgol-lsp")),
+                    ("version", Value::from(env!("CARGO_PKG_VERSION"))),
+                ]),
+            ),
+        ])
+    }
+
+    fn publish_diagnostics(&self, uri: &str) -> Value {
+        let text = self.documents.get(uri).map(String::as_str).unwrap_or("");
+        let analysis = analyze(text);
+
+        let diagnostics: Vec<Value> = analysis
+            .diagnostics
+            .iter()
+            .map(|diagnostic| {
+                let severity = match diagnostic.severity {
+                    Severity::Error => 1,
+                    Severity::Warning => 2,
+                    Severity::Note => 3,
+                };
+                let mut message = diagnostic.message(self.locale);
+                for note in &diagnostic.notes {
+                    message.push('\n');
+                    message.push_str(&note.render(self.locale));
+                }
+                let related: Vec<Value> = diagnostic
+                    .labels
+                    .iter()
+                    .map(|label| {
+                        Value::object(vec![
+                            ("location", self.location(uri, text, label.span)),
+                            ("message", Value::from(label.message.render(self.locale))),
+                        ])
+                    })
+                    .collect();
+
+                Value::object(vec![
+                    ("range", self.range(text, diagnostic.span)),
+                    ("severity", Value::from(severity as usize)),
+                    ("code", Value::from(Code::to_str(diagnostic.code))),
+                    ("source", Value::from("mgol")),
+                    ("message", Value::from(message)),
+                    ("relatedInformation", Value::Array(related)),
+                ])
+            })
+            .collect();
+
+        notification(
+            "textDocument/publishDiagnostics",
+            Value::object(vec![("uri", Value::from(uri)), ("diagnostics", Value::Array(diagnostics))]),
+        )
+    }
+
+    /// Hover on a variable: how it was declared, e.g. `inteiro A`.
+    fn hover(&self, _uri: &str, analysis: &Analysis, token: &Token) -> Option<Value> {
+        let (token_type, _) = declaration(analysis, token)?;
+        let lexeme = token.lexeme.clone().unwrap_or_default();
+
+        Some(Value::object(vec![(
+            "contents",
+            Value::object(vec![
+                ("kind", Value::from("markdown")),
+                (
+                    "value",
+                    Value::from(format!("```mgol\n{} {}\n```", TokenType::to_str(Some(token_type)), lexeme)),
+                ),
+            ]),
+        )]))
+    }
+
+    /// From a use of a variable to the identifier in its declaration.
+    fn definition(&self, uri: &str, analysis: &Analysis, token: &Token) -> Option<Value> {
+        let (_, span) = declaration(analysis, token)?;
+        let text = self.documents.get(uri).map(String::as_str).unwrap_or("");
+
+        Some(self.location(uri, text, span))
+    }
+
+    fn document_symbols(&self, text: &str, analysis: &Analysis) -> Value {
+        let mut declared: Vec<(&String, &Token)> = analysis
+            .symbol_table
+            .iter()
+            .filter(|(_, token)| token.class == Some(Class::Id) && token.token_type.is_some() && token.span.line > 0)
+            .collect();
+        declared.sort_by_key(|(_, token)| token.span.start);
+
+        let symbols: Vec<Value> = declared
+            .into_iter()
+            .map(|(lexeme, token)| {
+                Value::object(vec![
+                    ("name", Value::from(lexeme.as_str())),
+                    ("detail", Value::from(TokenType::to_str(token.token_type.clone()))),
+                    ("kind", Value::from(SYMBOL_VARIABLE)),
+                    ("range", self.range(text, token.span)),
+                    ("selectionRange", self.range(text, token.span)),
+                ])
+            })
+            .collect();
+
+        Value::Array(symbols)
+    }
+
+    /// Runs `f` on the identifier under the position of a
+    /// `TextDocumentPositionParams`; the result is `null` when there is none.
+    fn at_position<F>(&self, params: &Value, f: F) -> Result<Value, (i64, String)>
+    where
+        F: Fn(&Server, &str, &Analysis, &Token) -> Option<Value>,
+    {
+        let (uri, text) = self.document(params)?;
+        let (line, character) = match (
+            params.at(&["position", "line"]).and_then(Value::as_usize),
+            params.at(&["position", "character"]).and_then(Value::as_usize),
+        ) {
+            (Some(line), Some(character)) => (line, character),
+            _ => return Err((INVALID_PARAMS, String::from("posição inválida"))),
+        };
+
+        let analysis = analyze(&text);
+        let column = self.column(&text, line, character);
+        let token = analysis.tokens.iter().find(|token| {
+            token.class == Some(Class::Id)
+                && token.span.line == line + 1
+                && token.span.column <= column
+                && column <= token.span.end_column
+        });
+
+        Ok(token
+            .and_then(|token| f(self, &uri, &analysis, token))
+            .unwrap_or(Value::Null))
+    }
+
+    fn document(&self, params: &Value) -> Result<(String, String), (i64, String)> {
+        let uri = params.at(&["textDocument", "uri"]).and_then(Value::as_str);
+
+        match uri.and_then(|uri| Some((uri, self.documents.get(uri)?))) {
+            Some((uri, text)) => Ok((String::from(uri), text.clone())),
+            None => Err((INVALID_PARAMS, String::from("documento desconhecido"))),
+        }
+    }
+
+    fn location(&self, uri: &str, text: &str, span: Span) -> Value {
+        Value::object(vec![("uri", Value::from(uri)), ("range", self.range(text, span))])
+    }
+
+    fn range(&self, text: &str, span: Span) -> Value {
+        let position = |line: usize, column: usize| {
+            let line = line.max(1) - 1;
+            Value::object(vec![
+                ("line", Value::from(line)),
+                ("character", Value::from(self.character(text, line, column))),
+            ])
+        };
+
+        Value::object(vec![
+            ("start", position(span.line, span.column)),
+            ("end", position(span.end_line, span.end_column)),
+        ])
+    }
+
+    /// The protocol position of the 1-based `column` (in characters) of the
+    /// 0-based `line`.
+    fn character(&self, text: &str, line: usize, column: usize) -> usize {
+        let chars = column.max(1) - 1;
+        if self.utf32 {
+            return chars;
+        }
+
+        // Past the end of the line, e.g. at the end of the input, every
+        // column counts as one unit.
+        let source = text.split('\n').nth(line).unwrap_or("");
+        let units: usize = source.chars().take(chars).map(char::len_utf16).sum();
+        units + chars.saturating_sub(source.chars().count())
+    }
+
+    /// The 1-based column of a protocol position, the inverse of `character`.
+    fn column(&self, text: &str, line: usize, character: usize) -> usize {
+        if self.utf32 {
+            return character + 1;
+        }
+
+        let mut units = 0;
+        let mut column = 1;
+        for c in text.split('\n').nth(line).unwrap_or("").chars() {
+            if units >= character {
+                break;
+            }
+            units += c.len_utf16();
+            column += 1;
+        }
+
+        column
+    }
+}
+
+fn analyze(text: &str) -> Analysis {
+    let mut scanner = Scanner::from_str(text);
+    let mut parser = Parser::new();
+    parser.parse(&mut scanner);
+
+    Analysis {
+        diagnostics: parser.take_diagnostics(),
+        symbol_table: scanner.symbol_table,
+        tokens: Scanner::from_str(text).collect(),
+    }
+}
+
+/// The type and the declaration of the variable `token` refers to.
+fn declaration(analysis: &Analysis, token: &Token) -> Option<(TokenType, Span)> {
+    match analysis.symbol_table.get(token.lexeme.clone()?) {
+        Some(Token {
+            token_type: Some(token_type),
+            span,
+            ..
+        }) if span.line > 0 => Some((token_type, span)),
+        _ => None,
+    }
+}
+
+/// Every keyword and every declared variable.
+fn completions(analysis: &Analysis) -> Value {
+    let mut items: Vec<Value> = RESERVED_WORDS
+        .iter()
+        .map(|keyword| {
+            Value::object(vec![
+                ("label", Value::from(*keyword)),
+                ("kind", Value::from(COMPLETION_KEYWORD)),
+            ])
+        })
+        .collect();
+
+    let mut variables: Vec<(&String, &Token)> = analysis
+        .symbol_table
+        .iter()
+        .filter(|(_, token)| token.class == Some(Class::Id) && token.token_type.is_some())
+        .collect();
+    variables.sort_by(|a, b| a.0.cmp(b.0));
+    for (lexeme, token) in variables {
+        items.push(Value::object(vec![
+            ("label", Value::from(lexeme.as_str())),
+            ("kind", Value::from(COMPLETION_VARIABLE)),
+            ("detail", Value::from(TokenType::to_str(token.token_type.clone()))),
+        ]));
+    }
+
+    Value::Array(items)
+}
+
+fn notification(method: &str, params: Value) -> Value {
+    Value::object(vec![
+        ("jsonrpc", Value::from("2.0")),
+        ("method", Value::from(method)),
+        ("params", params),
+    ])
+}
+
+fn error_response(id: &Value, code: i64, message: &str) -> Value {
+    Value::object(vec![
+        ("jsonrpc", Value::from("2.0")),
+        ("id", id.clone()),
+        (
+            "error",
+            Value::object(vec![("code", Value::Number(code as f64)), ("message", Value::from(message))]),
+        ),
+    ])
+}
+
+/// Reads one message framed by a `Content-Length` header, or `None` at the
+/// end of the stream. A message the server cannot take, such as one longer
+/// than `MAX_MESSAGE_LENGTH`, is `Err` with the reason; its body is skipped,
+/// so the messages after it can still be read.
+pub fn read_message<R: BufRead>(reader: &mut R) -> io::Result<Option<Result<String, String>>> {
+    let mut length: Option<usize> = None;
+
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                length = value.trim().parse().ok();
+            }
+        }
+    }
+
+    let length = match length {
+        Some(length) => length,
+        None => return Err(io::Error::new(io::ErrorKind::InvalidData, "mensagem sem Content-Length")),
+    };
+
+    if length > MAX_MESSAGE_LENGTH {
+        let skipped = io::copy(&mut reader.by_ref().take(length as u64), &mut io::sink())?;
+        if skipped < length as u64 {
+            return Ok(None);
+        }
+        return Ok(Some(Err(format!(
+            "mensagem de {} bytes, maior que o limite de {}",
+            length, MAX_MESSAGE_LENGTH
+        ))));
+    }
+
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+
+    match String::from_utf8(body) {
+        Ok(body) => Ok(Some(Ok(body))),
+        Err(_) => Ok(Some(Err(String::from("mensagem não é UTF-8")))),
+    }
+}
+
+pub fn write_message<W: Write>(out: &mut W, message: &str) -> io::Result<()> {
+    write!(out, "Content-Length: {}\r\n\r\n{}", message.len(), message)?;
+    out.flush()
+}
+
+/// Serves requests from `reader` until `exit` or the end of the stream and
+/// returns the exit status.
+pub fn serve<R: BufRead, W: Write>(mut reader: R, mut writer: W) -> io::Result<i32> {
+    let mut server = Server::new();
+
+    while let Some(message) = read_message(&mut reader)? {
+        let responses = match message {
+            Ok(message) => server.handle(&message),
+            Err(error) => vec![error_response(&Value::Null, INVALID_REQUEST, &error).to_string()],
+        };
+        for response in responses {
+            write_message(&mut writer, &response)?;
+        }
+        if server.exited() {
+            break;
+        }
+    }
+
+    Ok(server.exit_code())
+}
@@ -1,34 +1,665 @@
-use std::fs::File;
+use std::{
+    env,
+    fs::{self, File},
+    io::{self, IsTerminal, Read, Write},
+    process,
+};
 
-use analisador_lexico::{scanner::Scanner, parser::Parser};
+use analisador_lexico::{
+    ast::Program,
+    catalog::{self, Locale, Text},
+    class::Class,
+    codegen::CodeGenerator,
+    diagnostic::{Diagnostic, Diagnostics, Phase, Severity},
+    first_follow::FirstFollow,
+    grammar::Grammar,
+    interpreter::Interpreter,
+    json,
+    lr0::LR0Automaton,
+    parse_tree::TreeFormat,
+    parser::Parser,
+    render::Renderer,
+    sarif,
+    scanner::Scanner,
+    table_builder::{self, TableMethod},
+    token_dump::{self, TokenFormat},
+    token_type::TokenType,
+    trace::{self, TraceFormat},
+};
+
+const USAGE: &str = "Uso: analisador_lexico <comando> [opções] <arquivo|->
+
+Comandos:
+    lex, tokens   lista os tokens (--format table|json|csv)
+    parse         análise léxica e sintática
+    trace         mostra cada passo da análise sintática (--format table|csv|html)
+    tree          mostra a árvore de derivação (--format text|dot|json)
+    check         análise léxica, sintática e semântica
+    emit-c        traduz o programa para C
+    run           executa o programa
+    symbols       mostra a tabela de símbolos (--format human|json)
+    grammar       mostra as produções, os conjuntos FIRST e FOLLOW e o resumo das
+                  tabelas (--tables, --method); com --format dot, o autômato LR(0)
+
+Opções:
+    -o, --output <arquivo>   escreve a saída no arquivo em vez da saída padrão
+    -f, --format <formato>   human, short, json ou sarif para os diagnósticos
+    --color <quando>         auto, always ou never
+    --lang <idioma>          pt-BR ou en para os diagnósticos (ou MGOL_LANG)
+    --method <método>        slr, lalr ou lr1
+    --grammar <arquivo>      gramática BNF usada pelo comando grammar
+    --tables                 inclui as tabelas action e goto no comando grammar
+
+Códigos de saída: 0 sem erros, 1 erros no programa, 2 erro de uso ou de E/S";
+
+/// Exit status when the program has errors (lexical, syntactic, semantic or
+/// at run time); usage and I/O problems exit with `FAILURE`.
+const ERRORS_FOUND: i32 = 1;
+const FAILURE: i32 = 2;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Human,
+    Short,
+    Json,
+    Sarif,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Color {
+    Auto,
+    Always,
+    Never,
+}
+
+struct Options {
+    command: String,
+    input: Option<String>,
+    output: Option<String>,
+    format: Option<String>,
+    color: Color,
+    locale: Option<Locale>,
+    method: TableMethod,
+    grammar: Option<String>,
+    tables: bool,
+}
+
+impl Options {
+    fn parse(args: &[String]) -> Result<Options, String> {
+        let mut args = args.iter();
+        let command = match args.next() {
+            Some(command) => command.clone(),
+            None => return Err(String::from("informe um comando")),
+        };
+
+        let mut options = Options {
+            command,
+            input: None,
+            output: None,
+            format: None,
+            color: Color::Auto,
+            locale: None,
+            method: TableMethod::Slr,
+            grammar: None,
+            tables: false,
+        };
+
+        while let Some(arg) = args.next() {
+            let mut value = |name: &str| match args.next() {
+                Some(value) => Ok(value.clone()),
+                None => Err(format!("a opção {} precisa de um valor", name)),
+            };
+
+            match arg.as_str() {
+                "-o" | "--output" => options.output = Some(value(arg)?),
+                "-f" | "--format" => options.format = Some(value(arg)?),
+                "--grammar" => options.grammar = Some(value(arg)?),
+                "--color" => {
+                    options.color = match value(arg)?.as_str() {
+                        "auto" => Color::Auto,
+                        "always" => Color::Always,
+                        "never" => Color::Never,
+                        color => return Err(format!("valor desconhecido '{}' para --color", color)),
+                    }
+                }
+                "--lang" => {
+                    let name = value(arg)?;
+                    options.locale = match Locale::from_name(&name) {
+                        Some(locale) => Some(locale),
+                        None => return Err(format!("idioma desconhecido '{}'", name)),
+                    }
+                }
+                "--method" => {
+                    options.method = match value(arg)?.as_str() {
+                        "slr" => TableMethod::Slr,
+                        "lalr" => TableMethod::Lalr,
+                        "lr1" => TableMethod::Lr1,
+                        method => return Err(format!("método desconhecido '{}'", method)),
+                    }
+                }
+                "--tables" => options.tables = true,
+                "-" => options.input = Some(arg.clone()),
+                flag if flag.starts_with('-') => {
+                    return Err(format!("opção desconhecida '{}'", flag))
+                }
+                _ if options.input.is_some() => {
+                    return Err(String::from("informe apenas um arquivo de entrada"))
+                }
+                _ => options.input = Some(arg.clone()),
+            }
+        }
+
+        Ok(options)
+    }
+
+    fn diagnostics_format(&self) -> Result<Format, String> {
+        match self.format.as_deref() {
+            None | Some("human") => Ok(Format::Human),
+            Some("short") => Ok(Format::Short),
+            Some("json") => Ok(Format::Json),
+            Some("sarif") => Ok(Format::Sarif),
+            Some(format) => Err(format!("formato desconhecido '{}'", format)),
+        }
+    }
+
+    /// How the input is called in rendered diagnostics.
+    fn input_name(&self) -> &str {
+        match self.input.as_deref() {
+            Some("-") | None => "<stdin>",
+            Some(path) => path,
+        }
+    }
+
+    /// Whether to colour what is written to a stream that is a terminal or
+    /// not. `NO_COLOR` turns colours off unless they are forced.
+    fn color(&self, terminal: bool) -> bool {
+        match self.color {
+            Color::Always => true,
+            Color::Never => false,
+            Color::Auto => terminal && env::var_os("NO_COLOR").is_none_or(|value| value.is_empty()),
+        }
+    }
+
+    /// `--lang`, else `MGOL_LANG`, else pt-BR.
+    fn locale(&self) -> Locale {
+        self.locale.or_else(Locale::from_env).unwrap_or_default()
+    }
+
+    /// How diagnostics are rendered for a stream that is a terminal or not.
+    fn renderer<'a>(&'a self, source: &'a str, terminal: bool) -> Renderer<'a> {
+        Renderer::new(self.input_name(), source)
+            .with_color(self.color(terminal))
+            .with_locale(self.locale())
+    }
+
+    fn source(&self) -> Result<String, String> {
+        let mut source = String::new();
+
+        match self.input.as_deref() {
+            Some("-") => match io::stdin().read_to_string(&mut source) {
+                Ok(_) => Ok(source),
+                Err(err) => Err(format!("não foi possível ler a entrada padrão: {}", err)),
+            },
+            Some(path) => match fs::read_to_string(path) {
+                Ok(source) => Ok(source),
+                Err(err) => Err(format!("não é possível abrir o arquivo {}: {}", path, err)),
+            },
+            None => Err(String::from("informe o arquivo de entrada ou '-'")),
+        }
+    }
+
+    fn output(&self) -> Result<Box<dyn Write>, String> {
+        match &self.output {
+            Some(path) => match File::create(path) {
+                Ok(file) => Ok(Box::new(file)),
+                Err(err) => Err(format!("não é possível criar o arquivo {}: {}", path, err)),
+            },
+            None => Ok(Box::new(io::stdout())),
+        }
+    }
+}
+
+/// The result of scanning and parsing a source: the program (when the
+/// parser got to the end) and the diagnostics of every phase.
+struct Analysis<'a> {
+    scanner: Scanner<&'a [u8]>,
+    program: Option<Program>,
+    diagnostics: Diagnostics,
+}
+
+impl<'a> Analysis<'a> {
+    fn new(source: &'a str) -> Analysis<'a> {
+        let mut scanner = Scanner::from_str(source);
+        let mut parser = Parser::new();
+        let program = parser.parse(&mut scanner);
+
+        Analysis {
+            diagnostics: parser.take_diagnostics(),
+            scanner,
+            program,
+        }
+    }
+
+    fn messages(&self, semantic: bool) -> Vec<&Diagnostic> {
+        self.diagnostics
+            .iter()
+            .filter(|diagnostic| semantic || diagnostic.phase() != Phase::Semantic)
+            .collect()
+    }
+}
 
 fn main() {
-    let path: &str =
-        "./src/source_code.txt";
-    let file: File = match File::open(path) {
-        Ok(file) => file,
-        Err(_) => panic!("Não é possível abrir o arquivo: {}", path),
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let options = match Options::parse(&args) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("Erro: {}\n\n{}", message, USAGE);
+            process::exit(FAILURE);
+        }
+    };
+
+    match run_command(&options) {
+        Ok(status) => process::exit(status),
+        Err(message) => {
+            eprintln!("Erro: {}", message);
+            process::exit(FAILURE);
+        }
+    }
+}
+
+fn run_command(options: &Options) -> Result<i32, String> {
+    match options.command.as_str() {
+        "lex" | "tokens" => lex(options),
+        "parse" => check(options, false),
+        "trace" => trace(options),
+        "tree" => tree(options),
+        "check" => check(options, true),
+        "emit-c" => emit_c(options),
+        "run" => run(options),
+        "symbols" => symbols(options),
+        "grammar" => grammar(options),
+        "-h" | "--help" | "help" => {
+            println!("{}", USAGE);
+            Ok(0)
+        }
+        command => Err(format!("comando desconhecido '{}'", command)),
+    }
+}
+
+/// `lex`: the tokens are the output; the diagnostics go to stderr.
+fn lex(options: &Options) -> Result<i32, String> {
+    let format = match options.format.as_deref() {
+        None | Some("human") => TokenFormat::Table,
+        Some(name) => match TokenFormat::from_name(name) {
+            Some(format) => format,
+            None => return Err(format!("formato desconhecido '{}'", name)),
+        },
     };
 
-    let mut scanner: Scanner = Scanner::new(file);
+    let source = options.source()?;
+    let mut scanner = Scanner::from_str(&source);
+    let mut out = options.output()?;
+
+    match token_dump::write_tokens(scanner.by_ref(), format, &mut out) {
+        Ok(_) => (),
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => (),
+        Err(err) => return Err(format!("não foi possível escrever os tokens: {}", err)),
+    }
+
+    let messages: Vec<&Diagnostic> = scanner.diagnostics().iter().collect();
+    if !messages.is_empty() {
+        let renderer = options.renderer(&source, io::stderr().is_terminal());
+        write_messages(&mut io::stderr(), &messages, Format::Human, options.locale(), &renderer)?;
+    }
+
+    Ok(status(scanner.error_count()))
+}
+
+/// `parse` and `check`: the diagnostics are the output of the command.
+fn check(options: &Options, semantic: bool) -> Result<i32, String> {
+    let format = options.diagnostics_format()?;
+    let source = options.source()?;
+    let analysis = Analysis::new(&source);
+    let messages = analysis.messages(semantic);
+    let renderer = options.renderer(&source, options.output.is_none() && io::stdout().is_terminal());
+
+    let mut out = options.output()?;
+    write_messages(&mut out, &messages, format, options.locale(), &renderer)?;
+    if matches!(format, Format::Human | Format::Short) && messages.is_empty() {
+        let text = catalog::template(options.locale(), Text::NoErrors);
+        write(&mut out, &format!("{}\n", text))?;
+    }
+
+    Ok(status(errors(&messages)))
+}
+
+/// `trace`: the steps of the parser are the output; the diagnostics go to
+/// stderr.
+fn trace(options: &Options) -> Result<i32, String> {
+    let format = match options.format.as_deref() {
+        None | Some("human") => TraceFormat::Table,
+        Some(name) => match TraceFormat::from_name(name) {
+            Some(format) => format,
+            None => return Err(format!("formato desconhecido '{}'", name)),
+        },
+    };
+
+    let source = options.source()?;
+    let mut scanner = Scanner::from_str(&source);
+    let mut parser = Parser::new().with_trace(true);
+    parser.parse(&mut scanner);
+
+    let messages: Vec<&Diagnostic> = parser
+        .diagnostics()
+        .iter()
+        .filter(|diagnostic| diagnostic.phase() != Phase::Semantic)
+        .collect();
+    if !messages.is_empty() {
+        let renderer = options.renderer(&source, io::stderr().is_terminal());
+        write_messages(&mut io::stderr(), &messages, Format::Human, options.locale(), &renderer)?;
+    }
+
+    let mut out = options.output()?;
+    match trace::write_trace(parser.trace(), format, &mut out).and_then(|_| out.flush()) {
+        Ok(_) => (),
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => (),
+        Err(err) => return Err(format!("não foi possível escrever os passos: {}", err)),
+    }
+
+    Ok(status(errors(&messages)))
+}
 
-    let mut parser = Parser::new();
+/// `tree`: the derivation of the program is the output; the diagnostics go
+/// to stderr.
+fn tree(options: &Options) -> Result<i32, String> {
+    let format = match options.format.as_deref() {
+        None | Some("human") => TreeFormat::Text,
+        Some(name) => match TreeFormat::from_name(name) {
+            Some(format) => format,
+            None => return Err(format!("formato desconhecido '{}'", name)),
+        },
+    };
+
+    let source = options.source()?;
+    let mut scanner = Scanner::from_str(&source);
+    let mut parser = Parser::new().with_tree(true);
     parser.parse(&mut scanner);
-    
-    // loop {
-    //     let token = scanner.scan();
-
-    //     let class = Class::to_str(token.class.clone());
-    //     let lexeme = String::from(token.lexeme.unwrap_or_else(|| String::from("Null")));
-    //     let token_type = TokenType::to_str(token.token_type.clone());
-        
-    //     println!("Classe: {}, Lexema: {}, Tipo: {}", class, lexeme, token_type);
-
-    //     if token.class.eq(&Some(Class::Eof)) {
-    //         break;
-    //     }
-    // }
-
-    // println!("\n\n<<--------- TABELA DE SIMBOLOS --------->>\n\n");
-    // scanner.show_symbol_table();
+
+    let messages: Vec<&Diagnostic> = parser
+        .diagnostics()
+        .iter()
+        .filter(|diagnostic| diagnostic.phase() != Phase::Semantic)
+        .collect();
+    if !messages.is_empty() {
+        let renderer = options.renderer(&source, io::stderr().is_terminal());
+        write_messages(&mut io::stderr(), &messages, Format::Human, options.locale(), &renderer)?;
+    }
+
+    if let Some(tree) = parser.tree() {
+        let text = match format {
+            TreeFormat::Text => tree.to_text(),
+            TreeFormat::Dot => tree.to_dot(),
+            TreeFormat::Json => format!("{}\n", tree.to_json()),
+        };
+        write(&mut options.output()?, &text)?;
+    }
+
+    Ok(status(errors(&messages)))
+}
+
+fn emit_c(options: &Options) -> Result<i32, String> {
+    let format = options.diagnostics_format()?;
+    let source = options.source()?;
+    let analysis = Analysis::new(&source);
+
+    let program = match checked_program(&analysis, options, &source, format)? {
+        Some(program) => program,
+        None => return Ok(ERRORS_FOUND),
+    };
+
+    let code = CodeGenerator::new().generate(program);
+    write(&mut options.output()?, &code)?;
+
+    Ok(0)
+}
+
+fn run(options: &Options) -> Result<i32, String> {
+    let format = options.diagnostics_format()?;
+    let source = options.source()?;
+    let analysis = Analysis::new(&source);
+
+    let program = match checked_program(&analysis, options, &source, format)? {
+        Some(program) => program,
+        None => return Ok(ERRORS_FOUND),
+    };
+
+    let mut interpreter = Interpreter::new(io::stdin().lock(), options.output()?);
+    match interpreter.run(program) {
+        Ok(_) => Ok(0),
+        Err(err) => {
+            eprintln!("{}", err);
+            Ok(ERRORS_FOUND)
+        }
+    }
+}
+
+fn symbols(options: &Options) -> Result<i32, String> {
+    let format = match options.diagnostics_format()? {
+        format @ (Format::Human | Format::Json) => format,
+        _ => {
+            return Err(format!(
+                "o comando symbols não aceita o formato '{}'",
+                options.format.as_deref().unwrap_or_default()
+            ))
+        }
+    };
+    let source = options.source()?;
+    let analysis = Analysis::new(&source);
+    let messages = analysis.messages(true);
+    if !messages.is_empty() {
+        let renderer = options.renderer(&source, io::stderr().is_terminal());
+        write_messages(&mut io::stderr(), &messages, format, options.locale(), &renderer)?;
+    }
+
+    let mut entries: Vec<(&String, Class, Option<TokenType>)> = analysis
+        .scanner
+        .symbol_table
+        .iter()
+        .filter_map(|(lexeme, token)| {
+            let class = token.class.clone()?;
+            Some((lexeme, class, token.token_type.clone()))
+        })
+        .collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+
+    let mut table = String::new();
+    match format {
+        Format::Human | Format::Short | Format::Sarif => {
+            table.push_str(&format!("{:<16} {:<12} {}\n", "Lexema", "Classe", "Tipo"));
+            for (lexeme, class, token_type) in entries {
+                table.push_str(&format!(
+                    "{:<16} {:<12} {}\n",
+                    lexeme,
+                    Class::to_str(Some(class)),
+                    TokenType::to_str(token_type)
+                ));
+            }
+        }
+        Format::Json => {
+            let entries: Vec<String> = entries
+                .into_iter()
+                .map(|(lexeme, class, token_type)| {
+                    format!(
+                        "{{\"lexema\":{},\"classe\":{},\"tipo\":{}}}",
+                        json::string(lexeme),
+                        json::string(&Class::to_str(Some(class))),
+                        match token_type {
+                            Some(token_type) => json::string(&TokenType::to_str(Some(token_type))),
+                            None => String::from("null"),
+                        }
+                    )
+                })
+                .collect();
+            table.push_str(&format!("[{}]\n", entries.join(",")));
+        }
+    }
+    write(&mut options.output()?, &table)?;
+
+    Ok(status(errors(&messages)))
+}
+
+fn grammar(options: &Options) -> Result<i32, String> {
+    let automaton = match options.format.as_deref() {
+        None | Some("human") | Some("text") => false,
+        Some("dot") => true,
+        Some(format) => return Err(format!("formato desconhecido '{}'", format)),
+    };
+
+    let grammar = match &options.grammar {
+        Some(path) => match Grammar::from_file(path) {
+            Ok(grammar) => grammar,
+            Err(errors) => {
+                for error in errors {
+                    eprintln!("{}", error);
+                }
+                return Ok(ERRORS_FOUND);
+            }
+        },
+        None => Grammar::new(),
+    };
+
+    if automaton {
+        write(&mut options.output()?, &LR0Automaton::new(&grammar).to_dot(&grammar))?;
+        return Ok(0);
+    }
+
+    let mut text = String::new();
+    for (n, rule) in grammar.rules().iter().enumerate() {
+        text.push_str(&format!("{}. {}\n", n, rule));
+    }
+    text.push('\n');
+    text.push_str(&FirstFollow::new(&grammar).report(&grammar));
+    text.push('\n');
+    text.push_str(&table_builder::report(&grammar));
+
+    let tables = table_builder::build_tables(&grammar, options.method);
+    if options.tables {
+        let mut csv: Vec<u8> = Vec::new();
+        writeln!(
+            csv,
+            "\nTabela action ({}):",
+            TableMethod::to_str(options.method)
+        )
+        .and_then(|_| tables.action_table.write_csv(&mut csv, &grammar))
+        .and_then(|_| writeln!(csv, "\nTabela goto:"))
+        .and_then(|_| tables.goto_table.write_csv(&mut csv, &grammar))
+        .map_err(|err| err.to_string())?;
+        text.push_str(&String::from_utf8_lossy(&csv));
+    }
+    write(&mut options.output()?, &text)?;
+
+    Ok(status(tables.conflicts.len()))
+}
+
+/// The program, when there are no errors. The diagnostics are reported on
+/// stderr, since stdout is reserved for the output of the command.
+fn checked_program<'a>(
+    analysis: &'a Analysis,
+    options: &Options,
+    source: &str,
+    format: Format,
+) -> Result<Option<&'a Program>, String> {
+    let messages = analysis.messages(true);
+    if !messages.is_empty() {
+        let renderer = options.renderer(source, io::stderr().is_terminal());
+        write_messages(&mut io::stderr(), &messages, format, options.locale(), &renderer)?;
+    }
+    if errors(&messages) > 0 {
+        return Ok(None);
+    }
+
+    Ok(analysis.program.as_ref())
+}
+
+fn write_messages<W: Write>(
+    out: &mut W,
+    messages: &[&Diagnostic],
+    format: Format,
+    locale: Locale,
+    renderer: &Renderer,
+) -> Result<(), String> {
+    let mut text = String::new();
+
+    match format {
+        Format::Human => {
+            for diagnostic in messages {
+                text.push_str(&renderer.render(diagnostic));
+                text.push('\n');
+            }
+            match errors(messages) {
+                0 => (),
+                1 => text.push_str(&format!("{}\n", catalog::template(locale, Text::OneError))),
+                n => text.push_str(&format!(
+                    "{}\n",
+                    catalog::format(locale, Text::ManyErrors, &[n.to_string()])
+                )),
+            }
+        }
+        Format::Short => {
+            let error = catalog::template(locale, Text::Error).to_uppercase();
+
+            for phase in [Phase::Lexical, Phase::Syntactic, Phase::Semantic] {
+                let diagnostics: Vec<&&Diagnostic> = messages
+                    .iter()
+                    .filter(|diagnostic| diagnostic.phase() == phase)
+                    .collect();
+
+                match diagnostics.len() {
+                    0 => continue,
+                    1 => text.push_str(&format!("{}\n", catalog::template(locale, Text::OneErrorIn(phase)))),
+                    n => text.push_str(&format!(
+                        "{}\n",
+                        catalog::format(locale, Text::ManyErrorsIn(phase), &[n.to_string()])
+                    )),
+                }
+                for (i, diagnostic) in diagnostics.iter().enumerate() {
+                    text.push_str(&format!("# {} {}\n    {}\n", error, i + 1, diagnostic.text(locale)));
+                }
+            }
+        }
+        Format::Json => {
+            text.push_str(&json::diagnostics(messages, locale));
+            text.push('\n');
+        }
+        Format::Sarif => {
+            text.push_str(&sarif::log(messages, locale, renderer.name()));
+            text.push('\n');
+        }
+    }
+
+    write(out, &text)
+}
+
+fn write<W: Write + ?Sized>(out: &mut W, text: &str) -> Result<(), String> {
+    match out.write_all(text.as_bytes()).and_then(|_| out.flush()) {
+        Ok(_) => Ok(()),
+        Err(err) if err.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        Err(err) => Err(format!("não foi possível escrever a saída: {}", err)),
+    }
+}
+
+/// Only errors fail a command; warnings and notes are just reported.
+fn errors(messages: &[&Diagnostic]) -> usize {
+    messages
+        .iter()
+        .filter(|diagnostic| diagnostic.severity == Severity::Error)
+        .count()
+}
+
+fn status(errors: usize) -> i32 {
+    match errors {
+        0 => 0,
+        _ => ERRORS_FOUND,
+    }
 }
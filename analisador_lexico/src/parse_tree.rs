@@ -0,0 +1,140 @@
+use crate::{class::Class, dot, json::Value, token::Token};
+
+/// The concrete syntax tree of a parse: every nonterminal reduced, with the
+/// symbols of its rule as children, down to the tokens.
+#[derive(Clone, Debug)]
+pub enum ParseTree {
+    Node {
+        symbol: String,
+        children: Vec<ParseTree>,
+    },
+    Leaf(Token),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TreeFormat {
+    Text,
+    Dot,
+    Json,
+}
+
+impl TreeFormat {
+    pub fn from_name(name: &str) -> Option<TreeFormat> {
+        match name {
+            "text" | "texto" => Some(TreeFormat::Text),
+            "dot" => Some(TreeFormat::Dot),
+            "json" => Some(TreeFormat::Json),
+            _ => None,
+        }
+    }
+}
+
+impl ParseTree {
+    /// How the tree is shown: the nonterminal, or the class of the token
+    /// followed by its lexeme when they differ.
+    pub fn label(&self) -> String {
+        match self {
+            ParseTree::Node { symbol, .. } => symbol.clone(),
+            ParseTree::Leaf(token) => {
+                let class = Class::to_str(token.class.clone());
+                match &token.lexeme {
+                    Some(lexeme) if *lexeme != class => format!("{} {}", class, lexeme),
+                    _ => class,
+                }
+            }
+        }
+    }
+
+    pub fn children(&self) -> &[ParseTree] {
+        match self {
+            ParseTree::Node { children, .. } => children,
+            ParseTree::Leaf(_) => &[],
+        }
+    }
+
+    /// One symbol per line, indented under its parent:
+    ///
+    /// ```text
+    /// P
+    /// ├── inicio
+    /// ├── V
+    /// │   ├── varinicio
+    /// ```
+    pub fn to_text(&self) -> String {
+        let mut text = format!("{}\n", self.label());
+        self.text_children("", &mut text);
+        text
+    }
+
+    fn text_children(&self, prefix: &str, text: &mut String) {
+        let children = self.children();
+
+        for (n, child) in children.iter().enumerate() {
+            let last = n + 1 == children.len();
+            let (branch, indent) = match last {
+                true => ("└── ", "    "),
+                false => ("├── ", "│   "),
+            };
+            text.push_str(&format!("{}{}{}\n", prefix, branch, child.label()));
+            child.text_children(&format!("{}{}", prefix, indent), text);
+        }
+    }
+
+    /// Nonterminals as `{"simbolo", "filhos"}` and tokens with their class,
+    /// lexeme and position.
+    pub fn to_json(&self) -> Value {
+        match self {
+            ParseTree::Node { symbol, children } => Value::object(vec![
+                ("simbolo", Value::from(symbol.as_str())),
+                (
+                    "filhos",
+                    Value::Array(children.iter().map(ParseTree::to_json).collect()),
+                ),
+            ]),
+            ParseTree::Leaf(token) => Value::object(vec![
+                ("classe", Value::from(Class::to_str(token.class.clone()))),
+                (
+                    "lexema",
+                    match &token.lexeme {
+                        Some(lexeme) => Value::from(lexeme.as_str()),
+                        None => Value::Null,
+                    },
+                ),
+                ("linha", Value::from(token.span.line)),
+                ("coluna", Value::from(token.span.column)),
+            ]),
+        }
+    }
+
+    /// A Graphviz digraph, with the nonterminals as ellipses and the tokens as
+    /// boxes, children left to right in the order of the rule.
+    pub fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph arvore {\n    ordering=out;\n");
+        let mut next = 0;
+        self.dot_node(&mut dot, &mut next);
+        dot.push_str("}\n");
+        dot
+    }
+
+    fn dot_node(&self, dot: &mut String, next: &mut usize) -> usize {
+        let id = *next;
+        *next += 1;
+
+        let shape = match self {
+            ParseTree::Node { .. } => "ellipse",
+            ParseTree::Leaf(_) => "box",
+        };
+        dot.push_str(&format!(
+            "    n{} [label={}, shape={}];\n",
+            id,
+            dot::string(&self.label()),
+            shape
+        ));
+        for child in self.children() {
+            let child = child.dot_node(dot, next);
+            dot.push_str(&format!("    n{} -> n{};\n", id, child));
+        }
+
+        id
+    }
+}
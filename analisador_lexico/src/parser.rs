@@ -1,13 +1,29 @@
+use std::{
+    collections::BTreeSet,
+    io::{self, BufRead},
+};
+
 use crate::{
+    ast::{Argument, Command, Condition, Declaration, Expression, Operand, Program},
+    catalog::Text,
     class::Class,
-    grammar::Grammar,
+    diagnostic::{Code, Diagnostic, Diagnostics, Message},
+    embedded_tables,
+    first_follow::FirstFollow,
+    grammar::{Grammar, GrammarRule},
     scanner::Scanner,
+    span::Span,
+    semantic::SemanticAnalyzer,
     slr_table::{ActionTable, GotoTable, SLRAction},
+    table_builder::{self, TableMethod},
+    parse_tree::ParseTree,
     token::Token,
+    token_type::TokenType,
+    trace::Step,
 };
 
 struct SyntaticStack {
-    stack: Vec<u8>,
+    stack: Vec<usize>,
 }
 
 impl SyntaticStack {
@@ -15,217 +31,1051 @@ impl SyntaticStack {
         SyntaticStack { stack: vec![0] }
     }
 
-    fn top(&self) -> u8 {
+    fn top(&self) -> usize {
         self.stack[self.stack.len() - 1]
     }
 
-    fn push(&mut self, n: u8) {
+    fn push(&mut self, n: usize) {
         self.stack.push(n);
     }
 
-    fn pop(&mut self, count: u8) {
+    fn pop(&mut self, count: usize) {
         for _ in 0..count {
             self.stack.pop();
         }
     }
+
+    fn len(&self) -> usize {
+        self.stack.len()
+    }
+
+    fn get(&self, depth: usize) -> usize {
+        self.stack[depth]
+    }
+
+    fn truncate(&mut self, len: usize) {
+        self.stack.truncate(len);
+    }
+}
+
+/// The nonterminals panic mode recovers on: the lists of commands and of
+/// declarations, so an error costs at most the command or declaration it
+/// is in.
+const SYNCHRONIZING: [&str; 4] = ["A", "CP", "CPR", "LV"];
+
+/// A single-token edit of the input, tried by the local repair at the
+/// offending token.
+enum Repair {
+    Insert(Class),
+    Delete,
+    Replace(Class),
+}
+
+/// A synchronizing nonterminal with its FIRST and FOLLOW sets, indexed by
+/// terminal id.
+struct Synchronizing {
+    symbol: &'static str,
+    non_terminal: usize,
+    declarations: bool,
+    first: Vec<bool>,
+    follow: Vec<bool>,
+}
+
+impl Synchronizing {
+    fn new(grammar: &Grammar, sets: &FirstFollow) -> Vec<Synchronizing> {
+        let terminals = |names: BTreeSet<String>| {
+            let mut set = vec![false; grammar.terminals().len()];
+            for name in names {
+                if let Some(id) = grammar.terminal_id(&name) {
+                    set[id] = true;
+                }
+            }
+            set
+        };
+
+        SYNCHRONIZING
+            .iter()
+            .filter_map(|name| {
+                Some(Synchronizing {
+                    symbol: name,
+                    non_terminal: grammar.non_terminal_id(name)?,
+                    declarations: *name == "LV",
+                    first: terminals(sets.first.get(*name).cloned().unwrap_or_default()),
+                    follow: terminals(sets.follow(name)),
+                })
+            })
+            .collect()
+    }
+
+    /// What stands for the part of the list lost to an error.
+    fn node(&self) -> Node {
+        match self.declarations {
+            true => Node::Declarations(Vec::new()),
+            false => Node::Commands(Vec::new()),
+        }
+    }
+}
+
+enum Node {
+    Token(Token),
+    Program(Program),
+    Declarations(Vec<Declaration>),
+    Declaration(Declaration),
+    Ids(Vec<Token>),
+    Commands(Vec<Command>),
+    Command(Command),
+    Argument(Argument),
+    Expression(Expression),
+    Operand(Operand),
+    Condition(Condition),
+    Header(Token, Condition),
+}
+
+impl Node {
+    fn reduce(rule: &GrammarRule, children: Vec<Node>) -> Node {
+        let mut children = children.into_iter();
+        let mut next = || children.next().expect("regra reduzida com filhos a menos");
+
+        match rule.left.text.as_str() {
+            "P" => {
+                next();
+                let declarations = reversed(next().declarations());
+                let commands = reversed(next().commands());
+                Node::Program(Program {
+                    declarations,
+                    commands,
+                })
+            }
+            "V" => {
+                next();
+                next()
+            }
+            "LV" => match next() {
+                Node::Declaration(declaration) => {
+                    let mut declarations = next().declarations();
+                    declarations.push(declaration);
+                    Node::Declarations(declarations)
+                }
+                _ => Node::Declarations(Vec::new()),
+            },
+            "D" => {
+                let data_type = next().token();
+                let ids = reversed(next().ids());
+                Node::Declaration(Declaration { data_type, ids })
+            }
+            "L" => {
+                let id = next().token();
+                let mut ids = match rule.right.len() {
+                    3 => {
+                        next();
+                        next().ids()
+                    }
+                    _ => Vec::new(),
+                };
+                ids.push(id);
+                Node::Ids(ids)
+            }
+            "A" | "CP" | "CPR" => match next() {
+                Node::Command(command) => {
+                    let mut commands = next().commands();
+                    commands.push(command);
+                    Node::Commands(commands)
+                }
+                _ => Node::Commands(Vec::new()),
+            },
+            "ES" => {
+                let keyword = next().token();
+                match keyword.class {
+                    Some(Class::Leia) => Node::Command(Command::Read {
+                        keyword,
+                        id: next().token(),
+                    }),
+                    _ => Node::Command(Command::Write {
+                        keyword,
+                        argument: next().argument(),
+                    }),
+                }
+            }
+            "ARG" => {
+                let token = next().token();
+                match token.class {
+                    Some(Class::Lit) => Node::Argument(Argument::Literal(token)),
+                    Some(Class::Num) => Node::Argument(Argument::Number(token)),
+                    _ => Node::Argument(Argument::Id(token)),
+                }
+            }
+            "CMD" => {
+                let id = next().token();
+                let rcb = next().token();
+                let value = next().expression();
+                Node::Command(Command::Assign { id, rcb, value })
+            }
+            "LD" => {
+                let left = next().operand();
+                match rule.right.len() {
+                    3 => Node::Expression(Expression::Arithmetic {
+                        left,
+                        operator: next().token(),
+                        right: next().operand(),
+                    }),
+                    _ => Node::Expression(Expression::Operand(left)),
+                }
+            }
+            "OPRD" => {
+                let token = next().token();
+                match token.class {
+                    Some(Class::Num) => Node::Operand(Operand::Number(token)),
+                    _ => Node::Operand(Operand::Id(token)),
+                }
+            }
+            "COND" | "R" => {
+                let (keyword, condition) = next().header();
+                let body = reversed(next().commands());
+                match rule.left.text.as_str() {
+                    "COND" => Node::Command(Command::If {
+                        keyword,
+                        condition,
+                        body,
+                    }),
+                    _ => Node::Command(Command::Repeat {
+                        keyword,
+                        condition,
+                        body,
+                    }),
+                }
+            }
+            "CAB" | "CABR" => {
+                let keyword = next().token();
+                next();
+                let condition = next().condition();
+                Node::Header(keyword, condition)
+            }
+            "EXP_R" => {
+                let left = next().operand();
+                let operator = next().token();
+                let right = next().operand();
+                Node::Condition(Condition {
+                    left,
+                    operator,
+                    right,
+                })
+            }
+            _ => next(),
+        }
+    }
+
+    fn token(self) -> Token {
+        match self {
+            Node::Token(token) => token,
+            _ => unreachable!("esperado um token"),
+        }
+    }
+
+    fn declarations(self) -> Vec<Declaration> {
+        match self {
+            Node::Declarations(declarations) => declarations,
+            _ => unreachable!("esperada uma lista de declarações"),
+        }
+    }
+
+    fn ids(self) -> Vec<Token> {
+        match self {
+            Node::Ids(ids) => ids,
+            _ => unreachable!("esperada uma lista de identificadores"),
+        }
+    }
+
+    fn commands(self) -> Vec<Command> {
+        match self {
+            Node::Commands(commands) => commands,
+            _ => unreachable!("esperada uma lista de comandos"),
+        }
+    }
+
+    fn argument(self) -> Argument {
+        match self {
+            Node::Argument(argument) => argument,
+            _ => unreachable!("esperado um argumento"),
+        }
+    }
+
+    fn expression(self) -> Expression {
+        match self {
+            Node::Expression(expression) => expression,
+            _ => unreachable!("esperada uma expressão"),
+        }
+    }
+
+    fn operand(self) -> Operand {
+        match self {
+            Node::Operand(operand) => operand,
+            _ => unreachable!("esperado um operando"),
+        }
+    }
+
+    fn condition(self) -> Condition {
+        match self {
+            Node::Condition(condition) => condition,
+            _ => unreachable!("esperada uma expressão relacional"),
+        }
+    }
+
+    fn header(self) -> (Token, Condition) {
+        match self {
+            Node::Header(keyword, condition) => (keyword, condition),
+            _ => unreachable!("esperado um cabeçalho de 'se' ou 'repita'"),
+        }
+    }
+}
+
+/// Right-recursive lists (LV, L, A, CP, CPR) are reduced from their last
+/// element to the first, so they are accumulated backwards and put in source
+/// order once, by the rule that consumes the whole list.
+fn reversed<T>(mut list: Vec<T>) -> Vec<T> {
+    list.reverse();
+    list
 }
 
 pub struct Parser {
     syntatic_stack: SyntaticStack,
+    semantic_stack: Vec<Node>,
+    semantic: SemanticAnalyzer,
     grammar: Grammar,
     action_table: ActionTable,
     goto_table: GotoTable,
+    terminal_ids: Vec<Option<usize>>,
     token_buffer: Vec<Token>,
-    error_messages: Vec<String>,
+    last_span: Span,
+    syntatic_errors: usize,
+    synchronizing: Vec<Synchronizing>,
+    /// The terminals a local repair may insert, with their classes.
+    insertable: Vec<(usize, Class)>,
+    /// Where panic mode last resumed, so it never resumes twice on the same
+    /// token.
+    resumed_at: Option<usize>,
+    /// Tokens shifted since panic mode last resumed. Errors within the first
+    /// few are most likely caused by the recovery itself and not reported.
+    shifted_since_resumed: Option<usize>,
+    diagnostics: Diagnostics,
+    /// The steps of the last parse, when tracing.
+    trace: Option<Vec<Step>>,
+    /// The symbol each state is reached on, to show the stack when tracing.
+    accessing: Vec<String>,
+    /// The subtrees of the symbols on the stack, when building the parse
+    /// tree.
+    tree_stack: Option<Vec<ParseTree>>,
+    tree: Option<ParseTree>,
+}
+
+impl Default for Parser {
+    fn default() -> Parser {
+        Parser::new()
+    }
 }
 
 impl Parser {
+    /// Uses the SLR(1) tables embedded at compile time.
     pub fn new() -> Parser {
-        Parser {
+        match Parser::with_tables(embedded_tables::action_table(), embedded_tables::goto_table()) {
+            Ok(parser) => parser,
+            Err(err) => panic!("As tabelas embutidas são inválidas: {}", err),
+        }
+    }
+
+    /// Builds the tables of the Mgol grammar with the given method.
+    pub fn with_method(method: TableMethod) -> Parser {
+        let tables = table_builder::build_tables(&Grammar::new(), method);
+        match Parser::with_tables(tables.action_table, tables.goto_table) {
+            Ok(parser) => parser,
+            Err(err) => panic!("As tabelas {} são inválidas: {}", TableMethod::to_str(method), err),
+        }
+    }
+
+    /// Uses alternate tables, e.g. loaded with `ActionTable::from_csv`. Their
+    /// columns must follow the symbol ids of the Mgol grammar, and every
+    /// shift, reduction and goto must stay inside them, or the error is
+    /// `InvalidData`.
+    pub fn with_tables(action_table: ActionTable, goto_table: GotoTable) -> io::Result<Parser> {
+        let grammar = Grammar::new();
+        check_tables(&grammar, &action_table, &goto_table)?;
+        let synchronizing = Synchronizing::new(&grammar, &FirstFollow::new(&grammar));
+
+        let mut terminal_ids: Vec<Option<usize>> = Vec::new();
+        let mut insertable: Vec<(usize, Class)> = Vec::new();
+        for (id, terminal) in grammar.terminals().iter().enumerate() {
+            if let Some(class) = Class::from_lexeme(terminal) {
+                if Class::spelling(&class).is_some() {
+                    insertable.push((id, class.clone()));
+                }
+                let index = class as usize;
+                if index >= terminal_ids.len() {
+                    terminal_ids.resize(index + 1, None);
+                }
+                terminal_ids[index] = Some(id);
+            }
+        }
+
+        Ok(Parser {
             syntatic_stack: SyntaticStack::new(),
-            grammar: Grammar::new(),
-            action_table: ActionTable::new(),
-            goto_table: GotoTable::new(),
+            semantic_stack: Vec::new(),
+            semantic: SemanticAnalyzer::new(),
+            grammar,
+            action_table,
+            goto_table,
+            terminal_ids,
             token_buffer: Vec::new(),
-            error_messages: Vec::new(),
+            last_span: Span::default(),
+            syntatic_errors: 0,
+            synchronizing,
+            insertable,
+            resumed_at: None,
+            shifted_since_resumed: None,
+            diagnostics: Diagnostics::new(),
+            trace: None,
+            accessing: Vec::new(),
+            tree_stack: None,
+            tree: None,
+        })
+    }
+
+    /// Builds the concrete parse tree during the reductions, see `tree`. Off
+    /// by default.
+    pub fn with_tree(mut self, tree: bool) -> Parser {
+        self.tree_stack = match tree {
+            true => Some(Vec::new()),
+            false => None,
+        };
+        self
+    }
+
+    /// Records every step of the parse, see `trace`. Off by default.
+    pub fn with_trace(mut self, trace: bool) -> Parser {
+        if !trace {
+            self.trace = None;
+            return self;
         }
+
+        let mut accessing: Vec<String> = vec![String::new(); self.action_table.states()];
+        for state in 0..self.action_table.states() {
+            for (terminal, name) in self.grammar.terminals().iter().enumerate() {
+                if let SLRAction::S(t) = self.action_table.get(state, terminal) {
+                    accessing[t] = name.clone();
+                }
+            }
+            for (non_terminal, name) in self.grammar.non_terminals().iter().enumerate() {
+                if let Some(goto) = self.goto_table.try_get(state, non_terminal) {
+                    accessing[goto] = name.clone();
+                }
+            }
+        }
+
+        self.trace = Some(Vec::new());
+        self.accessing = accessing;
+        self
     }
 
-    pub fn parse(&mut self, scanner: &mut Scanner) {
+    /// Parses a whole program. The parser can be reused: its stacks and
+    /// errors are reset at every call.
+    pub fn parse<R: BufRead>(&mut self, scanner: &mut Scanner<R>) -> Option<Program> {
+        self.reset();
+
+        let mut program: Option<Program> = None;
         let mut token = self.next_token(scanner);
         let mut a = token.class.clone();
 
         loop {
             let s = self.syntatic_stack.top();
-            let action = self.action_table.get(&(s, Class::to_str(a.clone())));
+            let action = match self.terminal_id(&a) {
+                Some(terminal) => self.action_table.get(s, terminal),
+                None => SLRAction::E(0),
+            };
+
+            if let Some(steps) = &mut self.trace {
+                let states = self.syntatic_stack.stack.clone();
+                steps.push(Step {
+                    symbols: states.iter().map(|state| self.accessing[*state].clone()).collect(),
+                    states,
+                    token: token.clone(),
+                    action,
+                    rule: match action {
+                        SLRAction::R(r) => Some(self.grammar.rules()[r].to_string()),
+                        _ => None,
+                    },
+                });
+            }
 
             match action {
                 SLRAction::S(t) => {
                     self.syntatic_stack.push(t);
+                    self.last_span = token.span;
+                    if let Some(shifted) = &mut self.shifted_since_resumed {
+                        *shifted += 1;
+                    }
+                    if let Some(trees) = &mut self.tree_stack {
+                        trees.push(ParseTree::Leaf(token.clone()));
+                    }
+                    self.semantic_stack.push(Node::Token(token));
                     token = self.next_token(scanner);
                     a = token.class.clone();
                 }
                 SLRAction::R(r) => {
-                    let rule = self.grammar.get_rule(r as usize);
-                    rule.show();
-                    #[allow(non_snake_case)]
-                    let A = rule.left;
-                    let beta = rule.right;
-                    self.syntatic_stack.pop(beta.len() as u8);
+                    let rule = &self.grammar.rules()[r];
+                    let children = self
+                        .semantic_stack
+                        .split_off(self.semantic_stack.len() - rule.right.len());
+                    let node = Node::reduce(rule, children);
+                    match &node {
+                        Node::Declaration(declaration) => self.semantic.declare(declaration, &mut scanner.symbol_table),
+                        Node::Command(command) => self.semantic.check_command(command, &scanner.symbol_table),
+                        Node::Header(_, condition) => {
+                            self.semantic.check_condition(condition, &scanner.symbol_table)
+                        }
+                        _ => (),
+                    }
+                    self.diagnostics.append(&mut self.semantic.take_diagnostics());
+                    self.semantic_stack.push(node);
+                    if let Some(trees) = &mut self.tree_stack {
+                        let children = trees.split_off(trees.len() - rule.right.len());
+                        trees.push(ParseTree::Node {
+                            symbol: rule.left.text.clone(),
+                            children,
+                        });
+                    }
+
+                    self.syntatic_stack.pop(rule.right.len());
                     let t = self.syntatic_stack.top();
-                    self.syntatic_stack
-                        .push(self.goto_table.get(&(t, A.text.clone())));
+                    self.syntatic_stack.push(self.goto_table.get(t, rule.left.id));
+                }
+                SLRAction::Acc => {
+                    if let Some(Node::Program(p)) = self.semantic_stack.pop() {
+                        program = Some(p);
+                    }
+                    if let Some(trees) = &mut self.tree_stack {
+                        self.tree = trees.pop();
+                    }
+                    break;
                 }
-                SLRAction::Acc => break,
                 SLRAction::E(e) => {
-                    self.token_buffer.push(token);
+                    const MAX_SYNTATIC_ERROR_COUNTER: usize = 100;
 
-                    if !self.error_recovery(e, scanner) {
+                    self.syntatic_errors += 1;
+                    if self.syntatic_errors > MAX_SYNTATIC_ERROR_COUNTER {
                         break;
                     }
 
-                    token = self.next_token(scanner);
+                    let expected = self.expected();
+                    self.token_buffer.push(token);
+                    token = match self.error_recovery(e, expected.clone(), scanner) {
+                        true => self.next_token(scanner),
+                        false => match self.panic_mode(e, expected, scanner) {
+                            Some(token) => token,
+                            None => break,
+                        },
+                    };
                     a = token.class.clone();
                 }
             }
         }
 
-        scanner.show_error_messages();
-        self.show_error_messages();
+        program
+    }
+
+    pub fn error_count(&self) -> usize {
+        self.diagnostics.error_count()
+    }
+
+    /// The lexical, syntactic and semantic diagnostics of the last parse, in
+    /// the order they were found.
+    pub fn diagnostics(&self) -> &Diagnostics {
+        &self.diagnostics
+    }
+
+    pub fn take_diagnostics(&mut self) -> Diagnostics {
+        std::mem::take(&mut self.diagnostics)
+    }
+
+    /// The derivation of the last program parsed, when built `with_tree`.
+    /// Lists lost to a syntax error are left without children.
+    pub fn tree(&self) -> Option<&ParseTree> {
+        self.tree.as_ref()
+    }
+
+    /// The steps of the last parse, empty unless built `with_trace`.
+    pub fn trace(&self) -> &[Step] {
+        self.trace.as_deref().unwrap_or(&[])
+    }
+
+    fn terminal_id(&self, class: &Option<Class>) -> Option<usize> {
+        match class {
+            Some(class) => self.terminal_ids.get(class.clone() as usize).copied().flatten(),
+            None => None,
+        }
+    }
+
+    fn reset(&mut self) {
+        self.syntatic_stack = SyntaticStack::new();
+        self.semantic_stack.clear();
+        self.semantic = SemanticAnalyzer::new();
+        self.token_buffer.clear();
+        self.last_span = Span::default();
+        self.syntatic_errors = 0;
+        self.resumed_at = None;
+        self.shifted_since_resumed = None;
+        self.diagnostics.clear();
+        if let Some(steps) = &mut self.trace {
+            steps.clear();
+        }
+        if let Some(trees) = &mut self.tree_stack {
+            trees.clear();
+        }
+        self.tree = None;
     }
 
-    fn next_token(&mut self, scanner: &mut Scanner) -> Token {
+    fn next_token<R: BufRead>(&mut self, scanner: &mut Scanner<R>) -> Token {
         if self.token_buffer.is_empty() {
-            return scanner.safe_scan();
+            let token = scanner.safe_scan();
+            self.diagnostics.append(&mut scanner.take_diagnostics());
+            token
         } else {
-            return self.token_buffer.pop().unwrap();
+            self.token_buffer.pop().unwrap()
         }
     }
 
-    fn error_recovery(&mut self, error_code: u8, scanner: &mut Scanner) -> bool {
-        const MAX_SYNTATIC_ERROR_COUNTER: u8 = 100;
-        static mut SYNTATIC_ERRORS: u8 = 0;
-
-        unsafe {
-            SYNTATIC_ERRORS += 1;
-            if SYNTATIC_ERRORS > MAX_SYNTATIC_ERROR_COUNTER {
-                return false;
+    /// The terminals with an action in the current state, as users write
+    /// them, for the error found there. SLR tables reduce on the whole FOLLOW
+    /// set, so terminals that are reduced on but then not shifted are left
+    /// out.
+    fn expected(&self) -> Message {
+        let state = self.syntatic_stack.top();
+        let mut spellings: Vec<String> = Vec::new();
+        for (terminal, name) in self.grammar.terminals().iter().enumerate() {
+            if matches!(self.action_table.get(state, terminal), SLRAction::E(_))
+                || self.simulate(&[Some(terminal)]) == 0
+            {
+                continue;
+            }
+            if let Some(class) = Class::from_lexeme(name) {
+                let free = matches!(class, Class::Id | Class::Num | Class::Lit | Class::Eof);
+                for spelling in Class::spellings(&class) {
+                    spellings.push(match free {
+                        true => String::from(spelling),
+                        false => format!("'{}'", spelling),
+                    });
+                }
             }
         }
 
+        Message::new(Text::Expected, vec![spellings.join(", ")])
+    }
+
+    /// Handles an error after `fim` by dropping the rest of the input, and
+    /// tries a local repair for the others. Returns false when they are left
+    /// to `panic_mode`.
+    fn error_recovery<R: BufRead>(
+        &mut self,
+        error_code: u8,
+        expected: Message,
+        scanner: &mut Scanner<R>,
+    ) -> bool {
         match error_code {
             1 => {
+                let span = match self.token_buffer.last() {
+                    Some(token) => token.span,
+                    None => self.last_span,
+                };
+                let mut eof = Token::new_from_lexeme("eof");
+                eof.span = span;
                 self.token_buffer.clear();
-                self.token_buffer.push(Token::new_from_lexeme("eof"));
+                self.token_buffer.push(eof);
 
-                self.error_messages.push(format!("[ES1] Erro na linha {}, coluna {}: nenhum código deve vir após a palavra reservada 'fim'", scanner.get_row(), scanner.get_col()));
+                self.diagnostics.push(
+                    Diagnostic::error(Code::ES1, span, Vec::new())
+                        .with_note(expected)
+                        .with_label(self.last_span, Message::from(Text::ProgramEndsHere)),
+                );
+                true
+            }
+            _ => self.local_repair(error_code, expected, scanner),
+        }
+    }
+
+    /// Burke–Fisher style repair: reads a few tokens ahead of the offending
+    /// one, the last in the buffer, and simulates on a copy of the stack every
+    /// insertion of a terminal before it, its deletion and its substitution,
+    /// in this order of cost. The edit that lets the parser go furthest, past
+    /// at least `REQUIRED` tokens, is applied to the buffer and reported;
+    /// insertions and substitutions only use terminals with a single
+    /// spelling.
+    fn local_repair<R: BufRead>(
+        &mut self,
+        error_code: u8,
+        expected: Message,
+        scanner: &mut Scanner<R>,
+    ) -> bool {
+        const LOOKAHEAD: usize = 4;
+        const REQUIRED: usize = 3;
 
-                return true;
+        let mut window: Vec<Token> = Vec::new();
+        while window.len() < LOOKAHEAD {
+            let token = self.next_token(scanner);
+            let eof = token.class == Some(Class::Eof);
+            window.push(token);
+            if eof {
+                break;
             }
-            2 => {
-                self.token_buffer.push(Token::new(
-                    Some(Class::Ptv),
-                    Some(String::from(";")),
-                    None,
-                ));
+        }
+        let input: Vec<Option<usize>> = window.iter().map(|token| self.terminal_id(&token.class)).collect();
+        let at_eof = window[0].class == Some(Class::Eof);
 
-                self.error_messages.push(format!(
-                    "[ES2] Erro sintático na linha {}, coluna {}: ausência de ';'",
-                    scanner.get_row(),
-                    scanner.get_col()
-                ));
-                return true;
+        // Whatever follows `fim` is dropped, so `fim` itself is only
+        // inserted where the input ends.
+        let mut candidates: Vec<(Repair, Vec<Option<usize>>)> = Vec::new();
+        for (terminal, class) in &self.insertable {
+            if *class == Class::Fim && !at_eof {
+                continue;
             }
-            3 => {
-                self.token_buffer.pop();
+            let mut edited = vec![Some(*terminal)];
+            edited.extend_from_slice(&input);
+            candidates.push((Repair::Insert(class.clone()), edited));
+        }
+        if !at_eof {
+            candidates.push((Repair::Delete, input[1..].to_vec()));
+            for (terminal, class) in &self.insertable {
+                if input[0] != Some(*terminal) && *class != Class::Fim {
+                    let mut edited = input.clone();
+                    edited[0] = Some(*terminal);
+                    candidates.push((Repair::Replace(class.clone()), edited));
+                }
+            }
+        }
 
-                self.error_messages.push(format!(
-                    "[ES3] Erro sintático na linha {}, coluna {}: múltiplos ';' na sequência",
-                    scanner.get_row(),
-                    scanner.get_col()
-                ));
-                return true;
+        // Progress is counted in tokens of the original input, and ties go
+        // to the cheapest edit. The fix of the routine the table names for
+        // the error needs one token less.
+        let mut repair: Option<Repair> = None;
+        let mut best = 0;
+        for (candidate, edited) in candidates {
+            let required = match routine_code(error_code, &candidate, &window[0]) {
+                Some(_) => REQUIRED - 1,
+                None => REQUIRED,
+            };
+            let shifted = self.simulate(&edited);
+            let consumed = match (shifted == edited.len(), &candidate) {
+                (true, _) => window.len(),
+                (false, Repair::Insert(_)) => shifted.saturating_sub(1),
+                (false, Repair::Delete) => shifted + 1,
+                (false, Repair::Replace(_)) => shifted,
+            };
+            if consumed >= required.min(window.len()) && consumed > best {
+                best = consumed;
+                repair = Some(candidate);
             }
-            4 => {
-                let token = self.token_buffer.pop();
+        }
 
-                self.error_messages.push(format!(
-                    "[ES4] Erro sintático na linha {}, coluna {}: token inválido após um ';'\n    NOTA: o token '{}' foi removido",
-                    scanner.get_row(),
-                    scanner.get_col(),
-                    token.unwrap().lexeme.unwrap()
-                ));
-                return true;
+        let mut window = window.into_iter();
+        let token = window.next().unwrap_or_else(|| Token::new_from_lexeme("eof"));
+        for rest in window.rev() {
+            self.token_buffer.push(rest);
+        }
+
+        let repair = match repair {
+            Some(repair) => repair,
+            None => {
+                self.token_buffer.push(token);
+                return false;
             }
-            5 => {
-                let token = self.token_buffer.pop();
+        };
 
-                self.token_buffer.push(Token::new(
-                    Some(Class::Abp),
-                    Some(String::from("(")),
-                    None,
-                ));
+        let span = token.span;
+        let missing = self.last_span.end_point();
+        let lexeme = token.lexeme.clone().unwrap_or_default();
+        let routine = routine_code(error_code, &repair, &token);
+        let diagnostic = match repair {
+            Repair::Insert(class) => {
+                let spelling = Class::spelling(&class).unwrap_or_default();
+                let (code, args) = match routine {
+                    Some(code) => (code, Vec::new()),
+                    None => (Code::ES9, vec![String::from(spelling), lexeme.clone()]),
+                };
 
-                self.error_messages.push(format!(
-                    "[ES5] Erro sintático na linha {}, coluna {}: esperado um '(' após a palavra reservada 'se'\n    NOTA: o token '{}' foi removido",
-                    scanner.get_row(),
-                    scanner.get_col(),
-                    token.unwrap().lexeme.unwrap()
-                ));
-                return true;
+                let mut inserted = Token::new(
+                    Some(class.clone()),
+                    Some(String::from(spelling)),
+                    TokenType::from_lexeme(spelling),
+                );
+                inserted.span = missing;
+                self.token_buffer.push(token);
+                self.token_buffer.push(inserted);
+
+                Diagnostic::error(code, missing, args)
+                    .with_note(expected)
+                    .with_note(Message::new(Text::Inserted, vec![String::from(spelling), lexeme]))
+                    .with_fix(missing, spelling, Message::new(Text::Insert, vec![String::from(spelling)]))
             }
-            6 => {
-                self.token_buffer.push(Token::new(
-                    Some(Class::Abp),
-                    Some(String::from("(")),
-                    None,
-                ));
+            Repair::Delete => {
+                let (code, args, fix) = match routine {
+                    Some(Code::ES3) => (Code::ES3, Vec::new(), Text::RemoveRepeated),
+                    Some(code) => (code, Vec::new(), Text::Remove),
+                    None => (Code::ES10, vec![lexeme.clone()], Text::Remove),
+                };
 
-                self.error_messages.push(format!(
-                    "[ES6] Erro sintático na linha {}, coluna {}: esperado um '(' após a palavra reservada 'se'",
-                    scanner.get_row(),
-                    scanner.get_col()
-                ));
-                return true;
+                Diagnostic::error(code, span, args)
+                    .with_note(expected)
+                    .with_note(Message::new(Text::TokenRemoved, vec![lexeme.clone()]))
+                    .with_fix(span, "", Message::new(fix, vec![lexeme]))
             }
-            7 => {
-                self.error_messages.push(format!(
-                    "[ES7] Erro sintático na linha {}, coluna {}: após um identificador deve vir um operador relacional, um operador aritimético, um ')' ou um ';'\n    NOTA: não é possível recuperar deste erro e portanto a análise foi interrompida",
-                    scanner.get_row(),
-                    scanner.get_col()
-                ));
-                return false;
+            Repair::Replace(class) => {
+                let spelling = Class::spelling(&class).unwrap_or_default();
+                let (code, args) = match routine {
+                    Some(code) => (code, Vec::new()),
+                    None => (Code::ES11, vec![String::from(spelling), lexeme.clone()]),
+                };
+
+                let mut replacement = Token::new(
+                    Some(class.clone()),
+                    Some(String::from(spelling)),
+                    TokenType::from_lexeme(spelling),
+                );
+                replacement.span = span;
+                self.token_buffer.push(replacement);
+
+                Diagnostic::error(code, span, args)
+                    .with_note(expected)
+                    .with_note(Message::new(Text::Replaced, vec![lexeme.clone(), String::from(spelling)]))
+                    .with_fix(span, spelling, Message::new(Text::Replace, vec![lexeme, String::from(spelling)]))
             }
-            8 => {
-                self.error_messages.push(format!(
-                    "[ES8] Erro sintático na linha {}, coluna {}: após um número deve vir um operador relacional, um operador aritimético, um ')' ou um ';'\n    NOTA: não é possível recuperar deste erro e portanto a análise foi interrompida",
-                    scanner.get_row(),
-                    scanner.get_col()
-                ));
-                return false;
+        };
+        self.diagnostics.push(diagnostic);
+
+        true
+    }
+
+    /// How many tokens of `input` the parser shifts from the current stack
+    /// before an error, all of them when it accepts or reaches the end of the
+    /// program, as whatever follows `fim` is dropped anyway. Only the states
+    /// are simulated, so nothing is reduced for real.
+    fn simulate(&self, input: &[Option<usize>]) -> usize {
+        let mut stack: Vec<usize> = self.syntatic_stack.stack.clone();
+
+        for (shifted, terminal) in input.iter().enumerate() {
+            let terminal = match terminal {
+                Some(terminal) => *terminal,
+                None => return shifted,
+            };
+
+            loop {
+                match self.action_table.get(stack[stack.len() - 1], terminal) {
+                    SLRAction::S(t) => {
+                        stack.push(t);
+                        break;
+                    }
+                    SLRAction::R(r) => {
+                        let rule = &self.grammar.rules()[r];
+                        if rule.right.len() >= stack.len() {
+                            return shifted;
+                        }
+                        stack.truncate(stack.len() - rule.right.len());
+                        match self.goto_table.try_get(stack[stack.len() - 1], rule.left.id) {
+                            Some(goto) => stack.push(goto),
+                            None => return shifted,
+                        }
+                    }
+                    SLRAction::Acc | SLRAction::E(1) => return input.len(),
+                    SLRAction::E(_) => return shifted,
+                }
             }
-            _ => {
-                self.error_messages.push(format!("[ES0] Erro na linha {}, coluna {}\n [ALERTA] Não é possível recuperar deste erro, portanto a análise foi interrompida", scanner.get_row(), scanner.get_col()));
+        }
 
-                return false;
+        input.len()
+    }
+
+    /// Panic mode: pops the stack down to a state with a goto on a
+    /// synchronizing nonterminal and skips input up to a token that can
+    /// start it, so the list goes on from there, or that can follow it, so
+    /// the list is taken as complete. Returns the token to resume at, or
+    /// `None` when the input ends first.
+    fn panic_mode<R: BufRead>(
+        &mut self,
+        error_code: u8,
+        expected: Message,
+        scanner: &mut Scanner<R>,
+    ) -> Option<Token> {
+        const QUIET_SHIFTS: usize = 3;
+
+        let report = !matches!(self.shifted_since_resumed, Some(shifted) if shifted < QUIET_SHIFTS);
+        let mut token = self.token_buffer.pop()?;
+        let (code, args) = match error_code {
+            7 => (Code::ES7, Vec::new()),
+            8 => (Code::ES8, Vec::new()),
+            _ => (Code::ES0, vec![token.lexeme.clone().unwrap_or_default()]),
+        };
+        let mut diagnostic = Diagnostic::error(code, token.span, args).with_note(expected);
+        let mut skipped: Option<Span> = None;
+
+        loop {
+            let again = self.resumed_at == Some(token.span.start);
+            if !again {
+                if let Some((depth, goto)) = self.synchronize(&token) {
+                    let goto = goto.map(|(state, synchronizing)| {
+                        (state, synchronizing.node(), synchronizing.symbol)
+                    });
+                    self.syntatic_stack.truncate(depth + 1);
+                    self.semantic_stack.truncate(depth);
+                    if let Some(trees) = &mut self.tree_stack {
+                        trees.truncate(depth);
+                    }
+                    if let Some((state, node, symbol)) = goto {
+                        self.syntatic_stack.push(state);
+                        self.semantic_stack.push(node);
+                        if let Some(trees) = &mut self.tree_stack {
+                            trees.push(ParseTree::Node {
+                                symbol: String::from(symbol),
+                                children: Vec::new(),
+                            });
+                        }
+                    }
+                    break;
+                }
             }
+
+            if token.class == Some(Class::Eof) {
+                if let Some(skipped) = skipped {
+                    diagnostic = diagnostic.with_label(skipped, Message::from(Text::Skipped));
+                }
+                if report {
+                    self.diagnostics.push(diagnostic.with_note(Message::from(Text::Unrecoverable)));
+                }
+                return None;
+            }
+
+            skipped = Some(match skipped {
+                Some(skipped) => skipped.to(token.span),
+                None => token.span,
+            });
+            self.last_span = token.span;
+            token = self.next_token(scanner);
         }
+
+        if let Some(skipped) = skipped {
+            diagnostic = diagnostic.with_label(skipped, Message::from(Text::Skipped));
+        }
+        if report {
+            self.diagnostics.push(diagnostic.with_note(Message::new(
+                Text::Resumed,
+                vec![token.lexeme.clone().unwrap_or_default()],
+            )));
+        }
+        self.resumed_at = Some(token.span.start);
+        self.shifted_since_resumed = Some(0);
+
+        Some(token)
     }
 
-    fn show_error_messages(&self) -> u8 {
-        let n = self.error_messages.len();
-        match n {
-            0 => (),
-            1 => println!("Foi encontrado 1 erro sintático"),
-            _ => println!("Foi encontrado {} erros sintáticos", n),
+    /// The deepest position of the stack, from the top, where `token` can
+    /// resume parsing, with the goto and the nonterminal to push when it
+    /// follows a synchronizing nonterminal instead of starting one.
+    fn synchronize(&self, token: &Token) -> Option<(usize, Option<(usize, &Synchronizing)>)> {
+        let terminal = self.terminal_id(&token.class)?;
+        let accepts = |state: usize| !matches!(self.action_table.get(state, terminal), SLRAction::E(_));
+
+        for depth in (0..self.syntatic_stack.len()).rev() {
+            let state = self.syntatic_stack.get(depth);
+
+            for synchronizing in &self.synchronizing {
+                let goto = match self.goto_table.try_get(state, synchronizing.non_terminal) {
+                    Some(goto) => goto,
+                    None => continue,
+                };
+
+                if synchronizing.first[terminal] && accepts(state) {
+                    return Some((depth, None));
+                }
+                if synchronizing.follow[terminal] && accepts(goto) {
+                    return Some((depth, Some((goto, synchronizing))));
+                }
+            }
         }
 
-        for i in 0..n {
-            let msg = &self.error_messages[i];
-            println!("# ERRO {}", i + 1);
-            println!("    {}", msg);
+        None
+    }
+}
+
+/// Whether the tables fit the grammar and each other, so the parser never
+/// looks up a cell outside them.
+fn check_tables(
+    grammar: &Grammar,
+    action_table: &ActionTable,
+    goto_table: &GotoTable,
+) -> io::Result<()> {
+    let invalid = |message: String| Err(io::Error::new(io::ErrorKind::InvalidData, message));
+    let states = action_table.states();
+    let rules = grammar.rules().len();
+
+    if action_table.terminals() != grammar.terminals().len() {
+        return invalid(format!(
+            "a tabela de ações tem {} colunas, mas a gramática tem {} terminais",
+            action_table.terminals(),
+            grammar.terminals().len()
+        ));
+    }
+    if goto_table.non_terminals() != grammar.non_terminals().len() {
+        return invalid(format!(
+            "a tabela de desvios tem {} colunas, mas a gramática tem {} não-terminais",
+            goto_table.non_terminals(),
+            grammar.non_terminals().len()
+        ));
+    }
+    if goto_table.states() != states {
+        return invalid(format!(
+            "a tabela de ações tem {} estados e a de desvios tem {}",
+            states,
+            goto_table.states()
+        ));
+    }
+    if states == 0 {
+        return invalid(String::from("as tabelas não têm nenhum estado"));
+    }
+
+    for state in 0..states {
+        for terminal in 0..action_table.terminals() {
+            match action_table.get(state, terminal) {
+                SLRAction::S(target) if target >= states => {
+                    return invalid(format!(
+                        "o estado {} desloca para o estado {}, mas as tabelas têm {} estados",
+                        state, target, states
+                    ))
+                }
+                SLRAction::R(rule) if rule >= rules => {
+                    return invalid(format!(
+                        "o estado {} reduz pela regra {}, mas a gramática tem {} regras",
+                        state, rule, rules
+                    ))
+                }
+                _ => (),
+            }
         }
+        for non_terminal in 0..goto_table.non_terminals() {
+            if let Some(goto) = goto_table.try_get(state, non_terminal).filter(|&goto| goto >= states) {
+                return invalid(format!(
+                    "o estado {} desvia para o estado {}, mas as tabelas têm {} estados",
+                    state, goto, states
+                ));
+            }
+        }
+    }
+
+    Ok(())
+}
 
-        n as u8
+/// The code of the recovery routine the tables chose for an error, ES2 to
+/// ES6, when `repair` of `token` is the fix it stands for.
+fn routine_code(error_code: u8, repair: &Repair, token: &Token) -> Option<Code> {
+    match (error_code, repair, &token.class) {
+        (2, Repair::Insert(Class::Ptv), _) => Some(Code::ES2),
+        (3, Repair::Delete, Some(Class::Ptv)) => Some(Code::ES3),
+        (4, Repair::Delete, _) => Some(Code::ES4),
+        (5, Repair::Replace(Class::Abp), _) => Some(Code::ES5),
+        (6, Repair::Insert(Class::Abp), _) => Some(Code::ES6),
+        _ => None,
     }
 }
@@ -0,0 +1,299 @@
+use crate::{
+    catalog::{self, Locale, Text},
+    diagnostic::{Code, Diagnostic, Severity},
+    span::Span,
+};
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[1;31m";
+const YELLOW: &str = "\x1b[1;33m";
+const GREEN: &str = "\x1b[1;32m";
+const BLUE: &str = "\x1b[1;34m";
+
+/// Renders diagnostics the way rustc does: a header with the code, the
+/// position, and the lines of the source involved, with the primary span
+/// underlined by `^` and each label by `-`.
+///
+/// ```text
+/// erro[EM2]: a variável 'A' já foi declarada
+///  --> exemplo.mgol:4:9
+///   |
+/// 3 | inteiro A;
+///   |         - declarada pela primeira vez aqui
+/// 4 | inteiro A;
+///   |         ^
+///   |
+///   = ajuda: remova a declaração repetida
+/// ```
+pub struct Renderer<'a> {
+    name: &'a str,
+    lines: Vec<&'a str>,
+    color: bool,
+    locale: Locale,
+}
+
+/// A span to underline on a single line of the source.
+struct Annotation {
+    column: usize,
+    width: usize,
+    primary: bool,
+    label: Option<String>,
+}
+
+impl<'a> Renderer<'a> {
+    /// `name` is how the source is called in the ` --> ` line, usually the
+    /// path of the file.
+    pub fn new(name: &'a str, source: &'a str) -> Renderer<'a> {
+        Renderer {
+            name,
+            lines: source.lines().collect(),
+            color: false,
+            locale: Locale::default(),
+        }
+    }
+
+    /// Colours the output with ANSI escapes, for terminals.
+    pub fn with_color(mut self, color: bool) -> Renderer<'a> {
+        self.color = color;
+        self
+    }
+
+    pub fn with_locale(mut self, locale: Locale) -> Renderer<'a> {
+        self.locale = locale;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        self.name
+    }
+
+    pub fn render(&self, diagnostic: &Diagnostic) -> String {
+        let (severity, style) = match diagnostic.severity {
+            Severity::Error => (Text::Error, RED),
+            Severity::Warning => (Text::Warning, YELLOW),
+            Severity::Note => (Text::Note, GREEN),
+        };
+        let severity = catalog::template(self.locale, severity);
+
+        let mut text = format!(
+            "{}{}\n",
+            self.paint(&format!("{}[{}]", severity, Code::to_str(diagnostic.code)), style),
+            self.paint(&format!(": {}", diagnostic.message(self.locale)), BOLD)
+        );
+
+        let span = diagnostic.span;
+        if span.line == 0 {
+            return self.footer(text, diagnostic, 0);
+        }
+
+        let mut lines: Vec<usize> = vec![span.line];
+        for label in &diagnostic.labels {
+            if label.span.line > 0 {
+                lines.push(label.span.line);
+            }
+        }
+        lines.sort_unstable();
+        lines.dedup();
+
+        let width = lines[lines.len() - 1].to_string().len();
+        let gutter = " ".repeat(width);
+        text.push_str(&format!(
+            "{}{} {}:{}:{}\n",
+            gutter,
+            self.paint("-->", BLUE),
+            self.name,
+            span.line,
+            span.column
+        ));
+        text.push_str(&format!("{} {}\n", gutter, self.paint("|", BLUE)));
+
+        let mut previous: Option<usize> = None;
+        for line in lines {
+            if matches!(previous, Some(previous) if line > previous + 1) {
+                text.push_str(&format!("{}\n", self.paint("...", BLUE)));
+            }
+            previous = Some(line);
+
+            let source = self.lines.get(line - 1).copied().unwrap_or("");
+            text.push_str(&format!(
+                "{} {}\n",
+                self.paint(&format!("{:>width$} |", line, width = width), BLUE),
+                source
+            ));
+
+            let mut annotations: Vec<Annotation> = Vec::new();
+            for label in &diagnostic.labels {
+                if label.span.line == line {
+                    let message = label.message.render(self.locale);
+                    annotations.push(annotation(label.span, source, false, Some(message)));
+                }
+            }
+            if span.line == line {
+                annotations.push(annotation(span, source, true, None));
+            }
+
+            for row in self.underlines(&annotations, source) {
+                text.push_str(&format!("{} {} {}\n", gutter, self.paint("|", BLUE), row));
+            }
+        }
+
+        self.footer(text, diagnostic, width)
+    }
+
+    /// The rows under a source line: one with every underline, ending with
+    /// the label of the rightmost annotation if it has one, then the other
+    /// labels, right to left, hanging from their spans.
+    fn underlines(&self, annotations: &[Annotation], source: &str) -> Vec<String> {
+        let end = annotations
+            .iter()
+            .map(|annotation| annotation.column + annotation.width)
+            .max()
+            .unwrap_or(0);
+
+        let mut marks: Vec<Option<bool>> = vec![None; end];
+        // The primary span is drawn last, so it wins where spans overlap.
+        for annotation in annotations.iter().filter(|annotation| !annotation.primary) {
+            for mark in &mut marks[annotation.column..annotation.column + annotation.width] {
+                *mark = Some(false);
+            }
+        }
+        for annotation in annotations.iter().filter(|annotation| annotation.primary) {
+            for mark in &mut marks[annotation.column..annotation.column + annotation.width] {
+                *mark = Some(true);
+            }
+        }
+
+        let mut row = String::new();
+        for (column, mark) in marks.iter().enumerate() {
+            match mark {
+                Some(true) => row.push_str(&self.paint("^", RED)),
+                Some(false) => row.push_str(&self.paint("-", BLUE)),
+                None => row.push(blank(source, column)),
+            }
+        }
+
+        let mut labeled: Vec<&Annotation> = annotations
+            .iter()
+            .filter(|annotation| annotation.label.is_some())
+            .collect();
+        labeled.sort_by_key(|annotation| annotation.column);
+
+        // A label goes after the underlines only when nothing is marked to
+        // the right of its span, or it would read as the label of that mark.
+        let mut rows: Vec<String> = Vec::new();
+        let inline = match labeled.last() {
+            Some(annotation) if annotation.column + annotation.width == end => labeled.pop(),
+            _ => None,
+        };
+        if let Some(label) = inline.and_then(|annotation| annotation.label.as_deref()) {
+            row.push(' ');
+            row.push_str(&self.paint(label, BLUE));
+        }
+        rows.push(row.trim_end().to_string());
+
+        for k in (0..labeled.len()).rev() {
+            let mut hanging = String::new();
+            let mut message = String::new();
+            for (j, annotation) in labeled[..=k].iter().enumerate() {
+                let column = annotation.column;
+                hanging.push_str(&indentation(source, hanging_width(&hanging), column));
+                hanging.push_str(&self.paint("|", BLUE));
+                message.push_str(&indentation(source, hanging_width(&message), column));
+                if j == k {
+                    message.push_str(&self.paint(annotation.label.as_deref().unwrap_or(""), BLUE));
+                } else {
+                    message.push_str(&self.paint("|", BLUE));
+                }
+            }
+            rows.push(hanging);
+            rows.push(message);
+        }
+
+        rows
+    }
+
+    fn footer(&self, mut text: String, diagnostic: &Diagnostic, width: usize) -> String {
+        if diagnostic.notes.is_empty() && diagnostic.fixes.is_empty() {
+            return text;
+        }
+
+        let gutter = " ".repeat(width);
+        let note = format!("= {}:", catalog::template(self.locale, Text::Note));
+        let help = format!("= {}:", catalog::template(self.locale, Text::Help));
+
+        text.push_str(&format!("{} {}\n", gutter, self.paint("|", BLUE)));
+        for message in &diagnostic.notes {
+            text.push_str(&format!(
+                "{} {} {}\n",
+                gutter,
+                self.paint(&note, BOLD),
+                message.render(self.locale)
+            ));
+        }
+        for fix in &diagnostic.fixes {
+            text.push_str(&format!(
+                "{} {} {}\n",
+                gutter,
+                self.paint(&help, BOLD),
+                fix.message.render(self.locale)
+            ));
+        }
+
+        text
+    }
+
+    fn paint(&self, text: &str, style: &str) -> String {
+        match self.color {
+            true => format!("{}{}{}", style, text, RESET),
+            false => String::from(text),
+        }
+    }
+}
+
+/// Where `span` falls on `source`, its line. A span that goes on to other
+/// lines is underlined up to the end of this one, and an empty span, where
+/// something is missing, gets a single mark.
+fn annotation(span: Span, source: &str, primary: bool, label: Option<String>) -> Annotation {
+    let column = span.column.saturating_sub(1);
+    let end = match span.end_line == span.line {
+        true => span.end_column.saturating_sub(1),
+        false => source.chars().count(),
+    };
+
+    Annotation {
+        column,
+        width: end.saturating_sub(column).max(1),
+        primary,
+        label,
+    }
+}
+
+/// The character under `column` when nothing is drawn there: tabs are kept
+/// so the marks line up with the source however wide tabs are shown.
+fn blank(source: &str, column: usize) -> char {
+    match source.chars().nth(column) {
+        Some('\t') => '\t',
+        _ => ' ',
+    }
+}
+
+fn indentation(source: &str, from: usize, to: usize) -> String {
+    (from..to).map(|column| blank(source, column)).collect()
+}
+
+/// How many columns of the source `row` covers, ignoring colour escapes.
+fn hanging_width(row: &str) -> usize {
+    let mut width = 0;
+    let mut escape = false;
+    for c in row.chars() {
+        match (escape, c) {
+            (false, '\x1b') => escape = true,
+            (true, 'm') => escape = false,
+            (true, _) => (),
+            (false, _) => width += 1,
+        }
+    }
+
+    width
+}
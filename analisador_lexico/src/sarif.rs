@@ -0,0 +1,162 @@
+use crate::{
+    catalog::{self, Locale, Text},
+    diagnostic::{Code, Diagnostic, Phase, Severity},
+    json,
+    span::Span,
+};
+
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// The diagnostics of one source as a SARIF 2.1.0 log with a single run.
+/// Every code is described in the rules of the driver, and results point
+/// at them by `ruleId` and `ruleIndex`. `uri` names the source in the
+/// physical locations.
+pub fn log(diagnostics: &[&Diagnostic], locale: Locale, uri: &str) -> String {
+    let uri = encode_uri(uri);
+    let rules: Vec<String> = Code::ALL.iter().map(|code| rule(*code, locale)).collect();
+    let results: Vec<String> = diagnostics
+        .iter()
+        .map(|diagnostic| result(diagnostic, locale, &uri))
+        .collect();
+
+    format!(
+        "{{\"$schema\":{},\"version\":\"2.1.0\",\"runs\":[{{\"tool\":{{\"driver\":{{\"name\":\"analisador_lexico\",\"version\":{},\"language\":{},\"rules\":[{}]}}}},\"language\":{},\"columnKind\":\"unicodeCodePoints\",\"results\":[{}]}}]}}",
+        json::string(SCHEMA),
+        json::string(env!("CARGO_PKG_VERSION")),
+        json::string(&Locale::to_str(locale)),
+        rules.join(","),
+        json::string(&Locale::to_str(locale)),
+        results.join(",")
+    )
+}
+
+/// The metadata of `code`: its message, with the arguments shown as `…` in
+/// the description and as `{0}`, `{1}`, ... in `messageStrings`.
+fn rule(code: Code, locale: Locale) -> String {
+    let placeholders = vec![String::from("…"); 3];
+
+    format!(
+        "{{\"id\":{},\"shortDescription\":{{\"text\":{}}},\"messageStrings\":{{\"default\":{{\"text\":{}}}}},\"defaultConfiguration\":{{\"level\":\"error\"}},\"properties\":{{\"phase\":{}}}}}",
+        json::string(&Code::to_str(code)),
+        json::string(&catalog::format(locale, Text::Message(code), &placeholders)),
+        json::string(catalog::template(locale, Text::Message(code))),
+        json::string(&Phase::to_str(Code::phase(code)))
+    )
+}
+
+fn result(diagnostic: &Diagnostic, locale: Locale, uri: &str) -> String {
+    let mut fields: Vec<String> = vec![
+        format!("\"ruleId\":{}", json::string(&Code::to_str(diagnostic.code))),
+        format!("\"ruleIndex\":{}", rule_index(diagnostic.code)),
+        format!("\"level\":{}", json::string(level(diagnostic.severity))),
+    ];
+
+    let mut message = diagnostic.message(locale);
+    for note in &diagnostic.notes {
+        message.push_str(&format!(
+            "\n{}: {}",
+            catalog::template(locale, Text::Note),
+            note.render(locale)
+        ));
+    }
+    let arguments: Vec<String> = diagnostic.args.iter().map(|arg| json::string(arg)).collect();
+    fields.push(format!(
+        "\"message\":{{\"text\":{},\"id\":\"default\",\"arguments\":[{}]}}",
+        json::string(&message),
+        arguments.join(",")
+    ));
+
+    fields.push(format!("\"locations\":[{{{}}}]", physical_location(uri, diagnostic.span)));
+
+    if !diagnostic.labels.is_empty() {
+        let related: Vec<String> = diagnostic
+            .labels
+            .iter()
+            .enumerate()
+            .map(|(id, label)| {
+                format!(
+                    "{{\"id\":{},{},\"message\":{{\"text\":{}}}}}",
+                    id,
+                    physical_location(uri, label.span),
+                    json::string(&label.message.render(locale))
+                )
+            })
+            .collect();
+        fields.push(format!("\"relatedLocations\":[{}]", related.join(",")));
+    }
+
+    if !diagnostic.fixes.is_empty() {
+        let fixes: Vec<String> = diagnostic
+            .fixes
+            .iter()
+            .map(|fix| {
+                format!(
+                    "{{\"description\":{{\"text\":{}}},\"artifactChanges\":[{{\"artifactLocation\":{{\"uri\":{}}},\"replacements\":[{{\"deletedRegion\":{},\"insertedContent\":{{\"text\":{}}}}}]}}]}}",
+                    json::string(&fix.message.render(locale)),
+                    json::string(uri),
+                    region(fix.span),
+                    json::string(&fix.replacement)
+                )
+            })
+            .collect();
+        fields.push(format!("\"fixes\":[{}]", fixes.join(",")));
+    }
+
+    format!("{{{}}}", fields.join(","))
+}
+
+fn rule_index(code: Code) -> usize {
+    Code::ALL.iter().position(|rule| *rule == code).unwrap_or(0)
+}
+
+fn level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Note => "note",
+    }
+}
+
+/// A `physicalLocation` property. Spans that point nowhere, such as the
+/// default one, have no region.
+fn physical_location(uri: &str, span: Span) -> String {
+    match span.line {
+        0 => format!("\"physicalLocation\":{{\"artifactLocation\":{{\"uri\":{}}}}}", json::string(uri)),
+        _ => format!(
+            "\"physicalLocation\":{{\"artifactLocation\":{{\"uri\":{}}},\"region\":{}}}",
+            json::string(uri),
+            region(span)
+        ),
+    }
+}
+
+/// Lines and columns are 1-based and the end column is exclusive, as in
+/// `Span`; offsets are in bytes.
+fn region(span: Span) -> String {
+    format!(
+        "{{\"startLine\":{},\"startColumn\":{},\"endLine\":{},\"endColumn\":{},\"byteOffset\":{},\"byteLength\":{}}}",
+        span.line.max(1),
+        span.column.max(1),
+        span.end_line.max(1),
+        span.end_column.max(1),
+        span.start,
+        span.len()
+    )
+}
+
+/// A relative reference for a path: `\` becomes `/` and everything but
+/// unreserved characters and `/` is percent-encoded.
+fn encode_uri(path: &str) -> String {
+    let mut uri = String::new();
+
+    for byte in path.replace('\\', "/").bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                uri.push(byte as char)
+            }
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+
+    uri
+}
@@ -4,35 +4,58 @@ use std::{
 };
 
 use crate::{
+    catalog::Text,
     class::Class,
+    diagnostic::{Code, Diagnostic, Diagnostics, Message},
     lexical_afd::{AFDState, Action, AFD},
+    span::Span,
     symbol_table::SymbolTable,
     token::Token,
     token_type::TokenType,
 };
 
-pub struct Scanner {
-    file: BufReader<File>,
+/// Reads tokens from any buffered source: a file (`Scanner::new`), a string
+/// (`Scanner::from_str`) or anything else through `Scanner::from_reader`.
+pub struct Scanner<R: BufRead = BufReader<File>> {
+    file: R,
     line: Vec<char>,
     cursor: (usize, usize),
+    offset: usize,
+    eof: bool,
+    finished: bool,
     pub symbol_table: SymbolTable,
-    error_messages: Vec<String>,
+    diagnostics: Diagnostics,
 }
 
 impl Scanner {
     pub fn new(file: File) -> Scanner {
-        let file: BufReader<File> = BufReader::new(file);
+        Scanner::from_reader(BufReader::new(file))
+    }
+}
+
+impl<'a> Scanner<&'a [u8]> {
+    #[allow(clippy::should_implement_trait)]
+    pub fn from_str(source: &'a str) -> Scanner<&'a [u8]> {
+        Scanner::from_reader(source.as_bytes())
+    }
+}
+
+impl<R: BufRead> Scanner<R> {
+    pub fn from_reader(file: R) -> Scanner<R> {
         let line: Vec<char> = Vec::new();
         let cursor: (usize, usize) = (0, 0);
         let symbol_table: SymbolTable = SymbolTable::new();
-        let error_messages: Vec<String> = Vec::new();
+        let diagnostics: Diagnostics = Diagnostics::new();
 
         Scanner {
             file,
             line,
             cursor,
+            offset: 0,
+            eof: false,
+            finished: false,
             symbol_table,
-            error_messages,
+            diagnostics,
         }
     }
 
@@ -50,6 +73,7 @@ impl Scanner {
 
     pub fn scan(&mut self) -> Token {
         let mut lexeme = String::new();
+        let mut span = Span::default();
         let mut afd = AFD::new();
 
         while let Some(c) = self.read_char() {
@@ -57,35 +81,51 @@ impl Scanner {
 
             match afd.action {
                 Action::GoBack => self.go_back(),
-                Action::Standard => lexeme.push(c),
+                Action::Standard => {
+                    let char_span = self.char_span(c);
+                    if lexeme.is_empty() {
+                        span = char_span;
+                    }
+                    span = span.to(char_span);
+                    lexeme.push(c);
+                }
                 Action::Clear => lexeme.clear(),
                 Action::ShowErrMessage => {
-                    self.insert_err_message(c, &afd.state);
-                    println!("{:?}", self.error_messages.last().unwrap());
+                    let char_span = self.char_span(c);
+                    if lexeme.is_empty() {
+                        span = char_span;
+                    }
+                    self.insert_err_message(c, &afd.state, char_span, span);
+                    span = span.to(char_span);
+                    lexeme.push(c);
                 }
                 Action::Idle => (),
             }
 
             if afd.done {
-                return self.assemble_token(lexeme, afd.state);
+                return self.assemble_token(lexeme, afd.state, span);
             }
         }
 
+        #[allow(clippy::len_zero)]
         if lexeme.len() > 0 {
             match afd.state {
-                AFDState::Accept(_) => return self.assemble_token(lexeme, afd.state),
+                AFDState::Accept(_) => return self.assemble_token(lexeme, afd.state, span),
                 AFDState::NonAccept(_) => {
                     afd.state = AFDState::Error(6);
-                    self.insert_err_message(' ', &afd.state);
-                    println!("{:?}", self.error_messages.last().unwrap());
+                    let opening = lexeme.chars().next().unwrap_or(' ');
+                    self.insert_err_message(opening, &afd.state, span, span);
 
-                    return self.assemble_token(lexeme, afd.state);
+                    return self.assemble_token(lexeme, afd.state, span);
                 }
                 _ => (),
             }
         }
 
-        Token::new(Some(Class::Eof), Some(String::from("EOF")), None)
+        let mut token = Token::new(Some(Class::Eof), Some(String::from("EOF")), None);
+        token.span = self.end_of_input();
+
+        token
     }
 
     pub fn get_col(&self) -> usize {
@@ -106,76 +146,132 @@ impl Scanner {
     }
 
     fn read_char(&mut self) -> Option<char> {
-        static mut IS_EOF: bool = false;
+        if self.eof {
+            return None;
+        }
 
         if self.cursor.1 == self.line.len() {
-            self.cursor.0 += 1;
-            self.cursor.1 = 0;
             let mut s = String::new();
             match self.file.read_line(&mut s) {
-                Ok(0) => unsafe {
-                    IS_EOF = true;
-                },
-                Ok(_) => self.line = s.chars().collect(),
-                Err(_) => (),
-            }
-        }
-
-        unsafe {
-            if IS_EOF {
-                return None;
+                Ok(0) => {
+                    self.eof = true;
+                    return None;
+                }
+                // A source that cannot be read, or is not UTF-8, ends where
+                // the reading stopped, but not silently.
+                Err(err) => {
+                    self.eof = true;
+                    let diagnostic =
+                        Diagnostic::error(Code::EL7, self.end_of_input(), vec![err.to_string()]);
+                    self.diagnostics.push(diagnostic);
+                    return None;
+                }
+                Ok(_) => {
+                    self.cursor.0 += 1;
+                    self.cursor.1 = 0;
+                    self.line = s.chars().collect();
+                }
             }
         }
 
         let c = self.line[self.cursor.1];
         self.cursor.1 += 1;
+        self.offset += c.len_utf8();
 
         Some(c)
     }
 
     fn go_back(&mut self) {
         self.cursor.1 -= 1;
+        self.offset -= self.line[self.cursor.1].len_utf8();
     }
 
-    fn insert_err_message(&mut self, c: char, afd_state: &AFDState) {
-        let row = self.cursor.0;
-        let col = self.cursor.1;
-
-        match afd_state {
-            AFDState::Error(0) => self.error_messages.push(format!(
-                "Erro Léxico 0: {:?} não pertence ao alfabeto. Linha [{}] Coluna [{}]",
-                c, row, col
-            )),
-            AFDState::Error(1) => self.error_messages.push(format!("Erro Léxico 1: {:?} não é início de nenhum token. Linha [{}] Coluna [{}]", c, row, col)),
-            AFDState::Error(2) => self.error_messages.push(format!("Erro Léxico 2: após um '.' em um [num] deve-se conter um dígito - {:?} foi encontrado. Linha [{}] Coluna [{}]", c, row, col)),
-            AFDState::Error(3) => self.error_messages.push(format!("Erro Léxico 3: após um 'e' ou 'E' em um [num] deve-se conter um dígito, um '+' ou um '-' - {:?} foi encontrado. Linha [{}] Coluna [{}]", c, row, col)),
-            AFDState::Error(4) => self.error_messages.push(format!("Erro Léxico 4: após um 'e+' ou 'E+' em um [num] dev-se conter um dígito - {:?} foi encontrado. Linha [{}] Coluna [{}]", c, row, col)),
-            AFDState::Error(5) => self.error_messages.push(format!("Erro Léxico 5: após um 'e-' ou 'E-' em um [num] dev-se conter um dígito - {:?} foi encontrado. Linha [{}] Coluna [{}]", c, row, col)),
-            AFDState::Error(6) => self.error_messages.push(format!("Erro Léxico 6: não foi encontrado o fechamento do comentário ou literal. Linha [{}] Coluna [{}]", row, col)),
-            _ => (),
+    /// The span of `c`, the character `read_char` has just returned.
+    fn char_span(&self, c: char) -> Span {
+        Span {
+            start: self.offset - c.len_utf8(),
+            end: self.offset,
+            line: self.cursor.0,
+            column: self.cursor.1,
+            end_line: self.cursor.0,
+            end_column: self.cursor.1 + 1,
         }
     }
 
-    pub fn show_error_messages(&self) -> u8 {
-        let n = self.error_messages.len();
-        match n {
-            0 => (),
-            1 => println!("Foi encontrado 1 erro léxico"),
-            _ => println!("Foi encontrado {} erros léxicos", n),
-        }
+    /// The empty span after the last character of the source.
+    fn end_of_input(&self) -> Span {
+        let (line, column) = match self.line.last() {
+            Some('\n') | None => (self.cursor.0 + 1, 1),
+            Some(_) => (self.cursor.0, self.line.len() + 1),
+        };
 
-        for i in 0..n {
-            let msg = &self.error_messages[i];
-            println!("# ERRO {}", i + 1);
-            println!("    {}", msg);
+        Span {
+            start: self.offset,
+            end: self.offset,
+            line,
+            column,
+            end_line: line,
+            end_column: column,
         }
+    }
+
+    /// `c` is the character that caused the error and `lexeme` the span of
+    /// what had been read of the token so far (or of `c`, if nothing had).
+    fn insert_err_message(&mut self, c: char, afd_state: &AFDState, span: Span, lexeme: Span) {
+        let character = format!("{:?}", c);
+        let code = match afd_state {
+            AFDState::Error(0) => Code::EL0,
+            AFDState::Error(1) => Code::EL1,
+            AFDState::Error(2) => Code::EL2,
+            AFDState::Error(3) => Code::EL3,
+            AFDState::Error(4) => Code::EL4,
+            AFDState::Error(5) => Code::EL5,
+            AFDState::Error(6) => Code::EL6,
+            _ => return,
+        };
+
+        let diagnostic = match code {
+            Code::EL0 | Code::EL1 => Diagnostic::error(code, span, vec![character.clone()]).with_fix(
+                span,
+                "",
+                Message::new(Text::RemoveCharacter, vec![character]),
+            ),
+            Code::EL6 => {
+                let closing = match c {
+                    '{' => "}",
+                    _ => "\"",
+                };
+                Diagnostic::error(code, span, Vec::new()).with_fix(
+                    span.end_point(),
+                    closing,
+                    Message::new(Text::CloseWith, vec![String::from(closing)]),
+                )
+            }
+            _ if lexeme == span => Diagnostic::error(code, span, vec![character]),
+            _ => Diagnostic::error(code, span, vec![character])
+                .with_label(lexeme, Message::from(Text::InThisNumber)),
+        };
 
-        n as u8
+        self.diagnostics.push(diagnostic);
     }
 
-    fn assemble_token(&mut self, lexeme: String, afd_state: AFDState) -> Token {
+    pub fn error_count(&self) -> usize {
+        self.diagnostics.error_count()
+    }
+
+    pub fn diagnostics(&self) -> &Diagnostics {
+        &self.diagnostics
+    }
+
+    /// Hands the diagnostics found so far over to the caller, e.g. the
+    /// parser, which collects those of every phase.
+    pub fn take_diagnostics(&mut self) -> Diagnostics {
+        std::mem::take(&mut self.diagnostics)
+    }
+
+    fn assemble_token(&mut self, lexeme: String, afd_state: AFDState, span: Span) -> Token {
         let mut class: Option<Class> = None;
-        let mut lexeme = Some(lexeme);
+        let lexeme = Some(lexeme);
         let mut token_type: Option<TokenType> = None;
 
         match afd_state {
@@ -198,7 +294,8 @@ impl Scanner {
             AFDState::Accept(9) => {
                 class = Some(Class::Id);
                 let lexeme_clone = lexeme.clone();
-                if let Some(token) = self.symbol_table.get(lexeme_clone.unwrap()) {
+                if let Some(mut token) = self.symbol_table.get(lexeme_clone.unwrap()) {
+                    token.span = span;
                     return token;
                 }
 
@@ -220,13 +317,30 @@ impl Scanner {
                 class = Some(Class::Num);
                 token_type = Some(TokenType::Inteiro);
             }
-            AFDState::Error(_) => {
-                class = Some(Class::Erro);
-                lexeme = None;
-            }
+            AFDState::Error(_) => class = Some(Class::Erro),
             _ => (),
         };
 
-        Token::new(class, lexeme, token_type)
+        let mut token = Token::new(class, lexeme, token_type);
+        token.span = span;
+
+        token
+    }
+}
+
+/// Yields every token, including `erro` tokens, up to and including the
+/// `eof` token.
+impl<R: BufRead> Iterator for Scanner<R> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        if self.finished {
+            return None;
+        }
+
+        let token = self.scan();
+        self.finished = token.class == Some(Class::Eof);
+
+        Some(token)
     }
 }
@@ -0,0 +1,226 @@
+use crate::{
+    ast::{Argument, Command, Condition, Declaration, Expression, Operand},
+    catalog::Text,
+    class::Class,
+    diagnostic::{Code, Diagnostic, Diagnostics, Message},
+    span::Span,
+    symbol_table::SymbolTable,
+    token::Token,
+    token_type::TokenType,
+};
+
+pub struct SemanticAnalyzer {
+    diagnostics: Diagnostics,
+}
+
+impl Default for SemanticAnalyzer {
+    fn default() -> SemanticAnalyzer {
+        SemanticAnalyzer::new()
+    }
+}
+
+impl SemanticAnalyzer {
+    pub fn new() -> SemanticAnalyzer {
+        SemanticAnalyzer {
+            diagnostics: Diagnostics::new(),
+        }
+    }
+
+    pub fn declare(&mut self, declaration: &Declaration, symbol_table: &mut SymbolTable) {
+        let token_type = declaration.data_type.token_type.clone();
+
+        for id in &declaration.ids {
+            let lexeme = id.lexeme.clone().unwrap_or_default();
+
+            match symbol_table.get(lexeme.clone()) {
+                Some(Token {
+                    token_type: Some(_),
+                    span: declared,
+                    ..
+                }) => self.diagnostics.push(
+                    Diagnostic::error(Code::EM2, id.span, vec![lexeme])
+                        .with_label(declared, Message::from(Text::FirstDeclaredHere))
+                        .with_fix(id.span, "", Message::from(Text::RemoveDeclaration)),
+                ),
+                _ => {
+                    // The declaration is kept with its span, so later errors
+                    // can point back at it.
+                    let mut token = Token::new(Some(Class::Id), Some(lexeme.clone()), token_type.clone());
+                    token.span = id.span;
+                    symbol_table.update(lexeme, token);
+                }
+            }
+        }
+    }
+
+    pub fn check_command(&mut self, command: &Command, symbol_table: &SymbolTable) {
+        match command {
+            Command::Read { id, .. } => {
+                self.type_of_id(id, symbol_table);
+            }
+            Command::Write {
+                argument: Argument::Id(id),
+                ..
+            } => {
+                self.type_of_id(id, symbol_table);
+            }
+            Command::Assign { id, value, .. } => {
+                let span = value.span();
+                let target = self.type_of_id(id, symbol_table);
+                let value = self.type_of_expression(value, symbol_table);
+
+                if let (Some(target), Some(value)) = (target, value) {
+                    if !is_assignable(&target, &value) {
+                        let lexeme = id.lexeme.clone().unwrap_or_default();
+                        let target = TokenType::to_str(Some(target));
+                        let mut diagnostic = Diagnostic::error(
+                            Code::EM3,
+                            span,
+                            vec![TokenType::to_str(Some(value)), lexeme.clone(), target.clone()],
+                        );
+                        if let Some(declared) = declaration_span(id, symbol_table) {
+                            diagnostic = diagnostic
+                                .with_label(declared, Message::new(Text::DeclaredAs, vec![lexeme, target]));
+                        }
+                        self.diagnostics.push(diagnostic);
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    pub fn check_condition(&mut self, condition: &Condition, symbol_table: &SymbolTable) {
+        let left = self.type_of_operand(&condition.left, symbol_table);
+        let right = self.type_of_operand(&condition.right, symbol_table);
+
+        if let (Some(left), Some(right)) = (left, right) {
+            if left == TokenType::Literal || right == TokenType::Literal {
+                let diagnostic = Diagnostic::error(
+                    Code::EM5,
+                    condition.span(),
+                    vec![
+                        condition.operator.lexeme.clone().unwrap_or_default(),
+                        TokenType::to_str(Some(left.clone())),
+                        TokenType::to_str(Some(right.clone())),
+                    ],
+                );
+                self.diagnostics.push(literal_labels(
+                    diagnostic,
+                    [(&condition.left, left), (&condition.right, right)],
+                ));
+            }
+        }
+    }
+
+    pub fn error_count(&self) -> usize {
+        self.diagnostics.error_count()
+    }
+
+    pub fn diagnostics(&self) -> &Diagnostics {
+        &self.diagnostics
+    }
+
+    pub fn take_diagnostics(&mut self) -> Diagnostics {
+        std::mem::take(&mut self.diagnostics)
+    }
+
+    fn type_of_expression(
+        &mut self,
+        expression: &Expression,
+        symbol_table: &SymbolTable,
+    ) -> Option<TokenType> {
+        match expression {
+            Expression::Operand(operand) => self.type_of_operand(operand, symbol_table),
+            Expression::Arithmetic {
+                left,
+                operator,
+                right,
+            } => {
+                let left_type = self.type_of_operand(left, symbol_table)?;
+                let right_type = self.type_of_operand(right, symbol_table)?;
+
+                match (&left_type, &right_type) {
+                    (TokenType::Inteiro, TokenType::Inteiro) => Some(TokenType::Inteiro),
+                    (TokenType::Literal, _) | (_, TokenType::Literal) => {
+                        let diagnostic = Diagnostic::error(
+                            Code::EM4,
+                            expression.span(),
+                            vec![operator.lexeme.clone().unwrap_or_default()],
+                        );
+                        self.diagnostics.push(literal_labels(
+                            diagnostic,
+                            [(left, left_type), (right, right_type)],
+                        ));
+                        None
+                    }
+                    _ => Some(TokenType::Real),
+                }
+            }
+        }
+    }
+
+    fn type_of_operand(&mut self, operand: &Operand, symbol_table: &SymbolTable) -> Option<TokenType> {
+        match operand {
+            Operand::Id(id) => self.type_of_id(id, symbol_table),
+            Operand::Number(number) => number.token_type.clone(),
+        }
+    }
+
+    fn type_of_id(&mut self, id: &Token, symbol_table: &SymbolTable) -> Option<TokenType> {
+        let lexeme = id.lexeme.clone().unwrap_or_default();
+
+        match symbol_table.get(lexeme.clone()) {
+            Some(Token {
+                token_type: Some(token_type),
+                ..
+            }) => Some(token_type),
+            _ => {
+                self.diagnostics.push(
+                    Diagnostic::error(Code::EM1, id.span, vec![lexeme])
+                        .with_note(Message::from(Text::DeclareBetween)),
+                );
+                None
+            }
+        }
+    }
+}
+
+/// Where the variable `id` was declared, if it was.
+fn declaration_span(id: &Token, symbol_table: &SymbolTable) -> Option<Span> {
+    match symbol_table.get(id.lexeme.clone().unwrap_or_default()) {
+        Some(Token {
+            token_type: Some(_),
+            span,
+            ..
+        }) if span != Span::default() => Some(span),
+        _ => None,
+    }
+}
+
+/// Points at each operand of type literal.
+fn literal_labels(mut diagnostic: Diagnostic, operands: [(&Operand, TokenType); 2]) -> Diagnostic {
+    for (operand, token_type) in operands {
+        if token_type == TokenType::Literal {
+            diagnostic = diagnostic.with_label(
+                operand.token().span,
+                Message::new(
+                    Text::HasTypeLiteral,
+                    vec![operand.token().lexeme.clone().unwrap_or_default()],
+                ),
+            );
+        }
+    }
+
+    diagnostic
+}
+
+fn is_assignable(target: &TokenType, value: &TokenType) -> bool {
+    matches!(
+        (target, value),
+        (TokenType::Inteiro, TokenType::Inteiro)
+            | (TokenType::Real, TokenType::Inteiro)
+            | (TokenType::Real, TokenType::Real)
+            | (TokenType::Literal, TokenType::Literal)
+    )
+}
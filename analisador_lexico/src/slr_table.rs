@@ -1,181 +1,563 @@
 use std::{
     collections::HashMap,
+    fmt,
     fs::File,
-    io::{self, BufRead},
+    io::{self, BufRead, Write},
 };
 
-#[derive(Clone, Debug)]
+use crate::grammar::{Grammar, END_MARKER};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum SLRAction {
-    S(u8),
-    R(u8),
+    S(usize),
+    R(usize),
     Acc,
     E(u8),
 }
 
 impl SLRAction {
-    fn from_str(s: &str) -> SLRAction {
+    /// Parses a cell of the CSV tables, `None` for anything but `S<n>`,
+    /// `R<n>`, `A` and `E<n>`.
+    fn from_str(s: &str) -> Option<SLRAction> {
         let mut chars = s.chars();
-        let kind = chars.next();
-        let n = chars.as_str().parse::<u8>().unwrap_or(0);
+        let kind = chars.next()?;
+        let n = chars.as_str();
+        if kind.eq_ignore_ascii_case(&'A') {
+            return match n.is_empty() {
+                true => Some(SLRAction::Acc),
+                false => None,
+            };
+        }
+        if n.is_empty() || !n.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
 
         match kind {
-            Some('S') | Some('s') => SLRAction::S(n),
-            Some('R') | Some('r') => SLRAction::R(n),
-            Some('A') | Some('a') => SLRAction::Acc,
-            Some('E') | Some('e') => SLRAction::E(n),
-            _ => SLRAction::E(0),
+            'S' | 's' => n.parse().ok().map(SLRAction::S),
+            'R' | 'r' => n.parse().ok().map(SLRAction::R),
+            'E' | 'e' => n.parse().ok().map(SLRAction::E),
+            _ => None,
+        }
+    }
+}
+
+/// Spelled as in the CSV tables: `S2`, `R3`, `A` and `E0`.
+impl fmt::Display for SLRAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SLRAction::S(state) => write!(f, "S{}", state),
+            SLRAction::R(rule) => write!(f, "R{}", rule),
+            SLRAction::Acc => write!(f, "A"),
+            SLRAction::E(code) => write!(f, "E{}", code),
+        }
+    }
+}
+
+/// Row-displacement compression: every row is laid over a single shared
+/// array at an offset (`base`) where its non-default cells don't collide with
+/// the cells already placed, and `check` records which row owns each slot.
+pub struct RowDisplacement<T: Copy + PartialEq> {
+    base: Vec<usize>,
+    check: Vec<usize>,
+    values: Vec<T>,
+    defaults: Vec<T>,
+    width: usize,
+}
+
+impl<T: Copy + PartialEq> RowDisplacement<T> {
+    pub fn new(cells: &[T], width: usize, default: fn(&[T]) -> T) -> RowDisplacement<T> {
+        let rows = match width {
+            0 => 0,
+            _ => cells.len() / width,
+        };
+        let mut base: Vec<usize> = Vec::with_capacity(rows);
+        let mut check: Vec<usize> = Vec::new();
+        let mut values: Vec<T> = Vec::new();
+        let mut defaults: Vec<T> = Vec::with_capacity(rows);
+
+        for row in 0..rows {
+            let cells = &cells[row * width..(row + 1) * width];
+            let default = default(cells);
+            let used: Vec<usize> = (0..width).filter(|&c| cells[c] != default).collect();
+
+            let mut offset = 0;
+            while used
+                .iter()
+                .any(|&c| offset + c < check.len() && check[offset + c] != usize::MAX)
+            {
+                offset += 1;
+            }
+
+            for &c in &used {
+                if offset + c >= check.len() {
+                    check.resize(offset + c + 1, usize::MAX);
+                    values.resize(offset + c + 1, default);
+                }
+                check[offset + c] = row;
+                values[offset + c] = cells[c];
+            }
+
+            base.push(offset);
+            defaults.push(default);
+        }
+
+        RowDisplacement {
+            base,
+            check,
+            values,
+            defaults,
+            width,
+        }
+    }
+
+    pub fn get(&self, row: usize, column: usize) -> T {
+        let slot = self.base[row] + column;
+        match self.check.get(slot) {
+            Some(&owner) if owner == row && column < self.width => self.values[slot],
+            _ => self.defaults[row],
         }
     }
+
+    /// Number of slots in the shared array, to compare against `rows * width`.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
+
+enum Storage<T: Copy + PartialEq> {
+    Dense(Vec<T>),
+    Compressed(RowDisplacement<T>),
 }
 
+/// The action table, indexed by state and by the terminal ids handed out by
+/// `Grammar::terminal_id`.
 pub struct ActionTable {
-    table: HashMap<(u8, String), SLRAction>,
+    states: usize,
+    terminals: usize,
+    storage: Storage<SLRAction>,
 }
 
 impl ActionTable {
-    pub fn new() -> ActionTable {
-        let terminals = [
-            "inicio",
-            "varinicio",
-            "varfim",
-            "pt_v",
-            "id",
-            "vir",
-            "inteiro",
-            "real",
-            "literal",
-            "leia",
-            "escreva",
-            "lit",
-            "num",
-            "rcb",
-            "opm",
-            "se",
-            "ab_p",
-            "fc_p",
-            "entao",
-            "opr",
-            "fimse",
-            "repita",
-            "fimrepita",
-            "fim",
-            "eof",
-        ];
-
-        let path = "./src/action_table.csv";
-        let actions_file = match File::open(path) {
-            Err(_) => panic!("Não foi possível abrir o arquivo {}", path),
-            Ok(file) => file,
-        };
+    pub fn new(states: usize, terminals: usize) -> ActionTable {
+        ActionTable {
+            states,
+            terminals,
+            storage: Storage::Dense(vec![SLRAction::E(0); states * terminals]),
+        }
+    }
 
-        let mut lines = io::BufReader::new(actions_file).lines();
+    /// Wraps cells already laid out as `state * terminals + terminal`, such
+    /// as the tables embedded by the build script.
+    pub fn from_cells(states: usize, terminals: usize, cells: Vec<SLRAction>) -> ActionTable {
+        assert_eq!(cells.len(), states * terminals);
 
-        lines.next();
+        ActionTable {
+            states,
+            terminals,
+            storage: Storage::Dense(cells),
+        }
+    }
 
-        let mut table = HashMap::new();
+    pub fn from_csv(path: &str, grammar: &Grammar) -> io::Result<ActionTable> {
+        ActionTable::from_reader(io::BufReader::new(File::open(path)?), grammar)
+    }
 
-        for line in lines {
-            if let Ok(okline) = line {
-                let actions = okline.split(",").collect::<Vec<&str>>();
-                let state = actions[0].parse::<u8>().unwrap();
+    /// Reads a table whose header names the terminal of each column, with one
+    /// row per state, e.g. `estado,inicio,...,$` followed by `0,S2,E0,...`.
+    pub fn from_reader<R: BufRead>(reader: R, grammar: &Grammar) -> io::Result<ActionTable> {
+        let (columns, rows) = read_csv(reader)?;
+        let columns: Vec<&str> = columns.iter().map(|column| csv_symbol(column)).collect();
+        let ids = column_ids(&columns, &grammar.terminals(), "terminal")?;
 
-                for i in 0..terminals.len() {
-                    let action = actions[i + 1];
-                    let terminal = terminals[i];
-                    table.insert((state, String::from(terminal)), SLRAction::from_str(action));
+        let states = rows.iter().map(|(state, _)| state + 1).max().unwrap_or(0);
+        let mut table = ActionTable::new(states, grammar.terminals().len());
+
+        let rules = grammar.rules().len();
+        for (state, cells) in rows {
+            for (i, cell) in cells.iter().enumerate() {
+                let action = match SLRAction::from_str(cell.trim()) {
+                    Some(action) => action,
+                    None => {
+                        return Err(invalid_data(format!(
+                            "ação inválida '{}' no estado {}",
+                            cell, state
+                        )))
+                    }
+                };
+                match action {
+                    SLRAction::S(target) if target >= states => {
+                        return Err(invalid_data(format!(
+                            "o estado {} desloca para o estado {}, mas a tabela tem {} estados",
+                            state, target, states
+                        )))
+                    }
+                    SLRAction::R(rule) if rule >= rules => {
+                        return Err(invalid_data(format!(
+                            "o estado {} reduz pela regra {}, mas a gramática tem {} regras",
+                            state, rule, rules
+                        )))
+                    }
+                    _ => table.insert(state, ids[i], action),
                 }
             }
         }
 
-        ActionTable { table }
+        Ok(table)
+    }
+
+    /// Writes the table in the format read by `from_reader`.
+    pub fn write_csv<W: Write>(&self, out: &mut W, grammar: &Grammar) -> io::Result<()> {
+        writeln!(out, "estado,{}", grammar.terminals().join(","))?;
+        for state in 0..self.states {
+            let actions: Vec<String> = (0..self.terminals)
+                .map(|terminal| self.get(state, terminal).to_string())
+                .collect();
+            writeln!(out, "{},{}", state, actions.join(","))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn states(&self) -> usize {
+        self.states
+    }
+
+    pub fn terminals(&self) -> usize {
+        self.terminals
     }
 
     pub fn len(&self) -> usize {
-        self.table.len()
+        self.states * self.terminals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Cells actually stored: `len()` while dense, fewer once compressed.
+    pub fn slots(&self) -> usize {
+        match &self.storage {
+            Storage::Dense(cells) => cells.len(),
+            Storage::Compressed(rows) => rows.len(),
+        }
     }
 
     pub fn show(&self) {
-        for key in self.table.keys() {
-            println!("{:?} -> {:?}", key, self.table.get(key).unwrap());
+        for state in 0..self.states {
+            for terminal in 0..self.terminals {
+                println!(
+                    "({}, {}) -> {:?}",
+                    state,
+                    terminal,
+                    self.get(state, terminal)
+                );
+            }
+        }
+    }
+
+    pub fn insert(&mut self, state: usize, terminal: usize, action: SLRAction) {
+        match &mut self.storage {
+            Storage::Dense(cells) => cells[state * self.terminals + terminal] = action,
+            Storage::Compressed(_) => panic!("Uma tabela compactada não pode ser alterada"),
+        }
+    }
+
+    pub fn get(&self, state: usize, terminal: usize) -> SLRAction {
+        match &self.storage {
+            Storage::Dense(cells) => cells[state * self.terminals + terminal],
+            Storage::Compressed(rows) => rows.get(state, terminal),
         }
     }
 
-    pub fn get(&self, key: &(u8, String)) -> SLRAction {
-        self.table.get(key).unwrap().clone()   
+    /// Replaces the flat array by a row-displacement table where each row's
+    /// most frequent error entry becomes its default.
+    pub fn compress(&mut self) {
+        if let Storage::Dense(cells) = &self.storage {
+            let rows = RowDisplacement::new(cells, self.terminals, most_common_error);
+            self.storage = Storage::Compressed(rows);
+        }
     }
 }
 
+/// The goto table, indexed by state and by the non-terminal ids handed out by
+/// `Grammar::non_terminal_id`.
 pub struct GotoTable {
-    table: HashMap<(u8, String), u8>,
+    states: usize,
+    non_terminals: usize,
+    storage: Storage<Option<usize>>,
 }
 
 impl GotoTable {
-    pub fn new() -> GotoTable {
-        let non_terminals = [
-            "P'",
-            "P", 
-            "V", 
-            "LV", 
-            "D", 
-            "L", 
-            "TIPO", 
-            "A", 
-            "ES", 
-            "ARG", 
-            "CMD", 
-            "LD", 
-            "OPRD", 
-            "COND", 
-            "CAB",
-            "EXP_R", 
-            "CP", 
-            "R", 
-            "CABR", 
-            "CPR",
-        ];
-
-        let path = "./src/goto_table.csv";
-        let goto_file = match File::open(path) {
-            Err(_) => panic!("Não foi possível abrir o arquivo {}", path),
-            Ok(file) => file,
-        };
+    pub fn new(states: usize, non_terminals: usize) -> GotoTable {
+        GotoTable {
+            states,
+            non_terminals,
+            storage: Storage::Dense(vec![None; states * non_terminals]),
+        }
+    }
 
-        let mut lines = io::BufReader::new(goto_file).lines();
+    pub fn from_cells(states: usize, non_terminals: usize, cells: Vec<Option<usize>>) -> GotoTable {
+        assert_eq!(cells.len(), states * non_terminals);
 
-        lines.next();
+        GotoTable {
+            states,
+            non_terminals,
+            storage: Storage::Dense(cells),
+        }
+    }
 
-        let mut table = HashMap::new();
+    pub fn from_csv(path: &str, grammar: &Grammar) -> io::Result<GotoTable> {
+        GotoTable::from_reader(io::BufReader::new(File::open(path)?), grammar)
+    }
 
-        for line in lines {
-            if let Ok(okline) = line {
-                let gotos = okline.split(",").collect::<Vec<&str>>();
-                let state = gotos[0].parse::<u8>().unwrap();
+    /// Reads a table whose header names the non-terminal of each column; a
+    /// `0` cell means there is no transition.
+    pub fn from_reader<R: BufRead>(reader: R, grammar: &Grammar) -> io::Result<GotoTable> {
+        let (columns, rows) = read_csv(reader)?;
+        let columns: Vec<&str> = columns.iter().map(String::as_str).collect();
+        let ids = column_ids(&columns, &grammar.non_terminals(), "não-terminal")?;
 
-                for i in 0..non_terminals.len() {
-                    let goto = gotos[i + 1].parse::<u8>().unwrap();
-                    if goto == 0 {
-                        continue;
+        let states = rows.iter().map(|(state, _)| state + 1).max().unwrap_or(0);
+        let mut table = GotoTable::new(states, grammar.non_terminals().len());
+
+        for (state, cells) in rows {
+            for (i, cell) in cells.iter().enumerate() {
+                match cell.trim().parse::<usize>() {
+                    Ok(0) => (),
+                    Ok(goto) if goto >= states => {
+                        return Err(invalid_data(format!(
+                            "o estado {} desvia para o estado {}, mas a tabela tem {} estados",
+                            state, goto, states
+                        )))
+                    }
+                    Ok(goto) => table.insert(state, ids[i], goto),
+                    Err(_) => {
+                        return Err(invalid_data(format!(
+                            "desvio inválido '{}' no estado {}",
+                            cell, state
+                        )))
                     }
-                    let non_terminal = non_terminals[i];
-                    table.insert((state, String::from(non_terminal)), goto);
                 }
             }
         }
 
-        GotoTable { table }
+        Ok(table)
+    }
+
+    /// Writes the table in the format read by `from_reader`.
+    pub fn write_csv<W: Write>(&self, out: &mut W, grammar: &Grammar) -> io::Result<()> {
+        writeln!(out, "estado,{}", grammar.non_terminals().join(","))?;
+        for state in 0..self.states {
+            let gotos: Vec<String> = (0..self.non_terminals)
+                .map(|non_terminal| self.try_get(state, non_terminal).unwrap_or(0).to_string())
+                .collect();
+            writeln!(out, "{},{}", state, gotos.join(","))?;
+        }
+
+        Ok(())
+    }
+
+    pub fn states(&self) -> usize {
+        self.states
+    }
+
+    pub fn non_terminals(&self) -> usize {
+        self.non_terminals
     }
 
     pub fn len(&self) -> usize {
-        self.table.len()
+        (0..self.states)
+            .map(|state| {
+                (0..self.non_terminals)
+                    .filter(|&non_terminal| self.try_get(state, non_terminal).is_some())
+                    .count()
+            })
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
     }
 
     pub fn show(&self) {
-        for key in self.table.keys() {
-            println!("{:?} -> {}", key, self.table.get(key).unwrap());
+        for state in 0..self.states {
+            for non_terminal in 0..self.non_terminals {
+                if let Some(goto) = self.try_get(state, non_terminal) {
+                    println!("({}, {}) -> {}", state, non_terminal, goto);
+                }
+            }
         }
     }
 
-    pub fn get(&self, key: &(u8, String)) -> u8 {
-        self.table.get(key).unwrap().clone()
+    pub fn insert(&mut self, state: usize, non_terminal: usize, goto: usize) {
+        match &mut self.storage {
+            Storage::Dense(cells) => cells[state * self.non_terminals + non_terminal] = Some(goto),
+            Storage::Compressed(_) => panic!("Uma tabela compactada não pode ser alterada"),
+        }
     }
+
+    pub fn get(&self, state: usize, non_terminal: usize) -> usize {
+        match self.try_get(state, non_terminal) {
+            Some(goto) => goto,
+            None => panic!(
+                "Não há desvio do estado {} com o não-terminal {}",
+                state, non_terminal
+            ),
+        }
+    }
+
+    pub fn compress(&mut self) {
+        if let Storage::Dense(cells) = &self.storage {
+            let rows = RowDisplacement::new(cells, self.non_terminals, |_| None);
+            self.storage = Storage::Compressed(rows);
+        }
+    }
+
+    pub fn try_get(&self, state: usize, non_terminal: usize) -> Option<usize> {
+        match &self.storage {
+            Storage::Dense(cells) => cells[state * self.non_terminals + non_terminal],
+            Storage::Compressed(rows) => rows.get(state, non_terminal),
+        }
+    }
+}
+
+/// A table's header (without the state column) and its rows.
+type Csv = (Vec<String>, Vec<(usize, Vec<String>)>);
+
+fn read_csv<R: BufRead>(reader: R) -> io::Result<Csv> {
+    let mut lines = reader.lines();
+
+    let columns: Vec<String> = match lines.next() {
+        Some(header) => header?
+            .trim()
+            .split(',')
+            .skip(1)
+            .map(String::from)
+            .collect(),
+        None => return Err(invalid_data(String::from("a tabela está vazia"))),
+    };
+
+    let mut rows: Vec<(usize, Vec<String>)> = Vec::new();
+    let mut lines_of_states: HashMap<usize, usize> = HashMap::new();
+    for (n, line) in lines.enumerate() {
+        let line = match line {
+            Ok(line) => line,
+            Err(err) if err.kind() == io::ErrorKind::InvalidData => {
+                return Err(invalid_data(format!("a linha {} não é UTF-8", n + 2)))
+            }
+            Err(err) => return Err(err),
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let cells: Vec<String> = line.trim().split(',').map(String::from).collect();
+        let state = match cells[0].parse::<usize>() {
+            Ok(state) => state,
+            Err(_) => return Err(invalid_data(format!("estado inválido '{}'", cells[0]))),
+        };
+        if cells.len() != columns.len() + 1 {
+            return Err(invalid_data(format!(
+                "o estado {} tem {} colunas, esperado {}",
+                state,
+                cells.len() - 1,
+                columns.len()
+            )));
+        }
+
+        if let Some(first) = lines_of_states.insert(state, n + 2) {
+            return Err(invalid_data(format!(
+                "a linha {} repete o estado {}, já definido na linha {}",
+                n + 2,
+                state,
+                first
+            )));
+        }
+
+        rows.push((state, cells[1..].to_vec()));
+    }
+
+    let states = rows.iter().map(|(state, _)| state + 1).max().unwrap_or(0);
+    if let Some(missing) = (0..states).find(|state| !lines_of_states.contains_key(state)) {
+        return Err(invalid_data(format!(
+            "falta a linha do estado {}, mas a tabela vai até o estado {}",
+            missing,
+            states - 1
+        )));
+    }
+
+    Ok((columns, rows))
+}
+
+/// The id of the symbol of each column of the header, which must name every
+/// one of `symbols`, the terminals or non-terminals of the grammar, once.
+fn column_ids(columns: &[&str], symbols: &[String], kind: &str) -> io::Result<Vec<usize>> {
+    let mut ids: Vec<usize> = Vec::new();
+
+    for (i, column) in columns.iter().enumerate() {
+        let id = match symbols.iter().position(|symbol| symbol == column) {
+            Some(id) => id,
+            None => {
+                return Err(invalid_data(format!(
+                    "o {} '{}' da coluna {} não pertence à gramática",
+                    kind,
+                    column,
+                    i + 2
+                )))
+            }
+        };
+        if let Some(first) = ids.iter().position(|&other| other == id) {
+            return Err(invalid_data(format!(
+                "as colunas {} e {} são ambas do {} '{}'",
+                first + 2,
+                i + 2,
+                kind,
+                column
+            )));
+        }
+        ids.push(id);
+    }
+
+    if let Some(missing) = (0..symbols.len()).find(|id| !ids.contains(id)) {
+        return Err(invalid_data(format!(
+            "falta a coluna do {} '{}'",
+            kind, symbols[missing]
+        )));
+    }
+
+    Ok(ids)
+}
+
+/// The CSV tables spell two terminals differently from the grammar.
+fn csv_symbol(column: &str) -> &str {
+    match column {
+        "então" => "entao",
+        "$" => END_MARKER,
+        column => column,
+    }
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+fn most_common_error(row: &[SLRAction]) -> SLRAction {
+    let mut best = SLRAction::E(0);
+    let mut best_count = 0;
+
+    for action in row {
+        if let SLRAction::E(_) = action {
+            let count = row.iter().filter(|other| *other == action).count();
+            if count > best_count {
+                best = *action;
+                best_count = count;
+            }
+        }
+    }
+
+    best
 }
@@ -2,11 +2,34 @@ use std::collections::HashMap;
 
 use crate::token::Token;
 
+/// The keywords of Mgol, which every symbol table starts with.
+pub const RESERVED_WORDS: [&str; 14] = [
+    "inicio",
+    "varinicio",
+    "varfim",
+    "escreva",
+    "leia",
+    "se",
+    "entao",
+    "fimse",
+    "repita",
+    "fimrepita",
+    "fim",
+    "inteiro",
+    "literal",
+    "real",
+];
 
 pub struct SymbolTable {
     pub hash_map: HashMap<String, Token>,
 }
 
+impl Default for SymbolTable {
+    fn default() -> SymbolTable {
+        SymbolTable::new()
+    }
+}
+
 impl SymbolTable {
     pub fn new() -> SymbolTable {
         let hash_map = HashMap::new();
@@ -35,30 +58,13 @@ impl SymbolTable {
         }
     }
 
-    pub fn iter(&self) -> std::collections::hash_map::Iter<String, Token> {
+    pub fn iter(&self) -> std::collections::hash_map::Iter<'_, String, Token> {
         self.hash_map.iter()
     }
 
 
     fn init_reserved_words(&mut self) {
-        let reserved_words: [&str; 14] = [
-            "inicio",
-            "varinicio",
-            "varfim",
-            "escreva",
-            "leia",
-            "se",
-            "entao",
-            "fimse",
-            "repita",
-            "fimrepita",
-            "fim",
-            "inteiro",
-            "literal",
-            "real",
-        ];
-
-        for lexeme in reserved_words {
+        for lexeme in RESERVED_WORDS {
             self.hash_map.insert(String::from(lexeme), Token::new_from_lexeme(lexeme));
         }
     }
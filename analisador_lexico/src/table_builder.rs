@@ -0,0 +1,293 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+};
+
+use crate::{
+    first_follow::FirstFollow,
+    grammar::{Grammar, END_MARKER},
+    lr0::{Item, LR0Automaton},
+    lr1::LR1Automaton,
+    slr_table::{ActionTable, GotoTable, SLRAction},
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConflictKind {
+    ShiftReduce,
+    ReduceReduce,
+}
+
+#[derive(Clone, Debug)]
+pub struct Conflict {
+    pub state: usize,
+    pub terminal: String,
+    pub kind: ConflictKind,
+    pub actions: Vec<SLRAction>,
+    pub items: Vec<String>,
+}
+
+impl fmt::Display for Conflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let kind = match self.kind {
+            ConflictKind::ShiftReduce => "shift/reduce",
+            ConflictKind::ReduceReduce => "reduce/reduce",
+        };
+
+        writeln!(
+            f,
+            "Conflito {} no estado {} com '{}': {:?}",
+            kind, self.state, self.terminal, self.actions
+        )?;
+        for item in &self.items {
+            writeln!(f, "    {}", item)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TableMethod {
+    Slr,
+    Lalr,
+    Lr1,
+}
+
+impl TableMethod {
+    pub fn to_str(method: TableMethod) -> String {
+        match method {
+            TableMethod::Slr => String::from("SLR(1)"),
+            TableMethod::Lalr => String::from("LALR(1)"),
+            TableMethod::Lr1 => String::from("LR(1)"),
+        }
+    }
+}
+
+pub struct ParseTables {
+    pub action_table: ActionTable,
+    pub goto_table: GotoTable,
+    pub conflicts: Vec<Conflict>,
+    pub states: usize,
+}
+
+pub fn build_slr(grammar: &Grammar) -> ParseTables {
+    let sets = FirstFollow::new(grammar);
+    let automaton = LR0Automaton::new(grammar);
+
+    build(
+        grammar,
+        &sets,
+        &automaton.states,
+        &automaton.transitions,
+        |_, item| sets.follow(&grammar.rules()[item.rule].left.text),
+    )
+}
+
+pub fn build_lalr(grammar: &Grammar) -> ParseTables {
+    let sets = FirstFollow::new(grammar);
+    let lr0 = LR0Automaton::new(grammar);
+    let lr1 = LR1Automaton::new(grammar, &sets);
+    let lookaheads = lr1.merge_cores(&lr0);
+
+    build(
+        grammar,
+        &sets,
+        &lr0.states,
+        &lr0.transitions,
+        |state, item| lookaheads.get(&(state, *item)).cloned().unwrap_or_default(),
+    )
+}
+
+pub fn build_lr1(grammar: &Grammar) -> ParseTables {
+    let sets = FirstFollow::new(grammar);
+    let lr1 = LR1Automaton::new(grammar, &sets);
+    let cores: Vec<Vec<Item>> = (0..lr1.len()).map(|state| lr1.core(state)).collect();
+
+    build(grammar, &sets, &cores, &lr1.transitions, |state, item| {
+        lr1.lookaheads(state, item)
+    })
+}
+
+pub fn build_tables(grammar: &Grammar, method: TableMethod) -> ParseTables {
+    match method {
+        TableMethod::Slr => build_slr(grammar),
+        TableMethod::Lalr => build_lalr(grammar),
+        TableMethod::Lr1 => build_lr1(grammar),
+    }
+}
+
+/// Builds the tables with every method and compares their size and conflicts.
+pub fn report(grammar: &Grammar) -> String {
+    let mut report = format!(
+        "{:<10} {:>7} {:>14} {:>15}\n",
+        "Método", "Estados", "Shift/reduce", "Reduce/reduce"
+    );
+    let mut details = String::new();
+
+    for method in [TableMethod::Slr, TableMethod::Lalr, TableMethod::Lr1] {
+        let tables = build_tables(grammar, method);
+        let count = |kind: ConflictKind| {
+            tables
+                .conflicts
+                .iter()
+                .filter(|conflict| conflict.kind == kind)
+                .count()
+        };
+
+        report.push_str(&format!(
+            "{:<10} {:>7} {:>14} {:>15}\n",
+            TableMethod::to_str(method),
+            tables.states,
+            count(ConflictKind::ShiftReduce),
+            count(ConflictKind::ReduceReduce)
+        ));
+
+        for conflict in &tables.conflicts {
+            details.push_str(&format!("[{}] {}", TableMethod::to_str(method), conflict));
+        }
+    }
+
+    report.push_str(&details);
+    report
+}
+
+/// Fills the action and goto tables for an already built automaton. Every
+/// method differs only in the lookaheads on which a complete item reduces.
+pub(crate) fn build<F>(
+    grammar: &Grammar,
+    sets: &FirstFollow,
+    states: &[Vec<Item>],
+    transitions: &[BTreeMap<String, usize>],
+    lookaheads: F,
+) -> ParseTables
+where
+    F: Fn(usize, &Item) -> BTreeSet<String>,
+{
+    let terminals = grammar.terminals();
+    let mut action_table = ActionTable::new(states.len(), terminals.len());
+    let mut goto_table = GotoTable::new(states.len(), grammar.non_terminals().len());
+    let mut conflicts: Vec<Conflict> = Vec::new();
+
+    for (state, items) in states.iter().enumerate() {
+        let mut actions: BTreeMap<String, SLRAction> = BTreeMap::new();
+
+        for (symbol, &target) in &transitions[state] {
+            match grammar.non_terminal_id(symbol) {
+                Some(non_terminal) => goto_table.insert(state, non_terminal, target),
+                None => {
+                    actions.insert(symbol.clone(), SLRAction::S(target));
+                }
+            }
+        }
+
+        for item in items.iter().filter(|item| item.is_complete(grammar)) {
+            let action = match item.rule {
+                0 => SLRAction::Acc,
+                rule => SLRAction::R(rule),
+            };
+            let lookaheads = match item.rule {
+                0 => BTreeSet::from([String::from(END_MARKER)]),
+                _ => lookaheads(state, item),
+            };
+
+            for terminal in lookaheads {
+                let existing = match actions.get(&terminal) {
+                    None => {
+                        actions.insert(terminal, action);
+                        continue;
+                    }
+                    Some(existing) => *existing,
+                };
+
+                let kind = match existing {
+                    SLRAction::S(_) => ConflictKind::ShiftReduce,
+                    _ => ConflictKind::ReduceReduce,
+                };
+                conflicts.push(Conflict {
+                    state,
+                    items: conflicting_items(grammar, items, &terminal),
+                    terminal: terminal.clone(),
+                    kind,
+                    actions: vec![existing, action],
+                });
+
+                if let (SLRAction::R(kept), SLRAction::R(new)) = (&existing, &action) {
+                    if new < kept {
+                        actions.insert(terminal, action);
+                    }
+                }
+            }
+        }
+
+        for (id, terminal) in terminals.iter().enumerate() {
+            let action = match actions.get(terminal) {
+                Some(action) => *action,
+                None => SLRAction::E(error_code(grammar, sets, items, &actions, terminal)),
+            };
+            action_table.insert(state, id, action);
+        }
+    }
+
+    ParseTables {
+        action_table,
+        goto_table,
+        conflicts,
+        states: states.len(),
+    }
+}
+
+fn conflicting_items(grammar: &Grammar, items: &[Item], terminal: &str) -> Vec<String> {
+    let mut unique: Vec<Item> = Vec::new();
+    for item in items {
+        let shifts = matches!(item.next_symbol(grammar), Some(symbol) if symbol.text == terminal);
+        if (shifts || item.is_complete(grammar)) && !unique.contains(item) {
+            unique.push(*item);
+        }
+    }
+
+    unique.iter().map(|item| item.show(grammar)).collect()
+}
+
+/// Chooses which of the parser's recovery routines (ES1..ES8) handles an empty
+/// cell, from the items of the state. Anything unrecognized is `E0`. The
+/// shipped tables, filled by hand, disagree on the routine of a few cells.
+fn error_code(
+    grammar: &Grammar,
+    sets: &FirstFollow,
+    items: &[Item],
+    actions: &BTreeMap<String, SLRAction>,
+    terminal: &str,
+) -> u8 {
+    for item in items {
+        let rule = &grammar.rules()[item.rule];
+
+        if item.is_complete(grammar) {
+            match (rule.left.text.as_str(), rule.right.last()) {
+                (_, Some(last)) if last.text == "fim" => return 1,
+                ("OPRD", Some(last)) if last.text == "id" => return 7,
+                ("OPRD", Some(last)) if last.text == "num" => return 8,
+                (_, Some(last)) if last.text == "pt_v" && terminal == "pt_v" => return 3,
+                (_, Some(last)) if last.text == "pt_v" => return 4,
+                _ => (),
+            }
+        } else if item.dot > 0
+            && rule.right[item.dot - 1].text == "se"
+            && rule.right[item.dot].text == "ab_p"
+        {
+            let (first, _) = sets.first_of(&rule.right[item.dot + 1..]);
+            return match first.contains(terminal) {
+                true => 6,
+                false => 5,
+            };
+        }
+    }
+
+    let expects_pt_v = actions.contains_key("pt_v")
+        && actions
+            .keys()
+            .all(|expected| expected == "pt_v" || expected == "vir");
+    match expects_pt_v {
+        true => 2,
+        false => 0,
+    }
+}
@@ -1,10 +1,11 @@
-use crate::{class::Class, token_type::TokenType};
+use crate::{class::Class, span::Span, token_type::TokenType};
 
 #[derive(Clone, Debug)]
 pub struct Token {
     pub class: Option<Class>,
     pub lexeme: Option<String>,
     pub token_type: Option<TokenType>,
+    pub span: Span,
 }
 
 impl Token {
@@ -17,6 +18,7 @@ impl Token {
             class,
             lexeme,
             token_type,
+            span: Span::default(),
         }
     }
 
@@ -25,6 +27,7 @@ impl Token {
             class: Class::from_lexeme(lexeme),
             lexeme: Some(String::from(lexeme)),
             token_type: TokenType::from_lexeme(lexeme),
+            span: Span::default(),
         }
     }
 
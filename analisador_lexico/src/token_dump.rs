@@ -0,0 +1,104 @@
+use std::io::{self, Write};
+
+use crate::{class::Class, json, token::Token, token_type::TokenType};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TokenFormat {
+    Table,
+    Json,
+    Csv,
+}
+
+impl TokenFormat {
+    pub fn from_name(name: &str) -> Option<TokenFormat> {
+        match name {
+            "table" | "tabela" => Some(TokenFormat::Table),
+            "json" => Some(TokenFormat::Json),
+            "csv" => Some(TokenFormat::Csv),
+            _ => None,
+        }
+    }
+}
+
+/// Writes the token stream one token per line: an aligned table, JSON lines
+/// or CSV with a header. Every format includes the span of the token.
+pub fn write_tokens<W, I>(tokens: I, format: TokenFormat, out: &mut W) -> io::Result<()>
+where
+    W: Write,
+    I: IntoIterator<Item = Token>,
+{
+    match format {
+        TokenFormat::Table => writeln!(
+            out,
+            "{:<12} {:<24} {:<10} {:<9} {:<9} Bytes",
+            "Classe", "Lexema", "Tipo", "Início", "Fim"
+        )?,
+        TokenFormat::Csv => writeln!(
+            out,
+            "classe,lexema,tipo,linha,coluna,linha_fim,coluna_fim,inicio,fim"
+        )?,
+        TokenFormat::Json => (),
+    }
+
+    for token in tokens {
+        let class = Class::to_str(token.class.clone());
+        let lexeme = token.lexeme.clone().unwrap_or_else(|| String::from("Null"));
+        let token_type = TokenType::to_str(token.token_type.clone());
+        let span = token.span;
+
+        match format {
+            TokenFormat::Table => writeln!(
+                out,
+                "{:<12} {:<24} {:<10} {:<9} {:<9} {}..{}",
+                class,
+                lexeme.escape_debug().to_string(),
+                token_type,
+                format!("{}:{}", span.line, span.column),
+                format!("{}:{}", span.end_line, span.end_column),
+                span.start,
+                span.end
+            )?,
+            TokenFormat::Json => writeln!(
+                out,
+                "{{\"classe\":{},\"lexema\":{},\"tipo\":{},\"linha\":{},\"coluna\":{},\"linha_fim\":{},\"coluna_fim\":{},\"inicio\":{},\"fim\":{}}}",
+                json::string(&class),
+                match &token.lexeme {
+                    Some(lexeme) => json::string(lexeme),
+                    None => String::from("null"),
+                },
+                match &token.token_type {
+                    Some(_) => json::string(&token_type),
+                    None => String::from("null"),
+                },
+                span.line,
+                span.column,
+                span.end_line,
+                span.end_column,
+                span.start,
+                span.end
+            )?,
+            TokenFormat::Csv => writeln!(
+                out,
+                "{},{},{},{},{},{},{},{},{}",
+                class,
+                csv_field(&lexeme),
+                token_type,
+                span.line,
+                span.column,
+                span.end_line,
+                span.end_column,
+                span.start,
+                span.end
+            )?,
+        }
+    }
+
+    Ok(())
+}
+
+pub(crate) fn csv_field(text: &str) -> String {
+    match text.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", text.replace('"', "\"\"")),
+        false => String::from(text),
+    }
+}
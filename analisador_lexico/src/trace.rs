@@ -0,0 +1,166 @@
+use std::io::{self, Write};
+
+use crate::{class::Class, slr_table::SLRAction, token::Token, token_dump::csv_field};
+
+/// One step of the parser: the stack as it was when the action was chosen,
+/// the token on the input and the action.
+#[derive(Clone, Debug)]
+pub struct Step {
+    pub states: Vec<usize>,
+    /// The symbol each state was reached on, none for the first one.
+    pub symbols: Vec<String>,
+    pub token: Token,
+    pub action: SLRAction,
+    /// The rule of a reduction.
+    pub rule: Option<String>,
+}
+
+impl Step {
+    /// States and symbols interleaved, bottom first: `0 inicio 2 V 4`.
+    pub fn stack(&self) -> String {
+        let mut stack = String::new();
+
+        for (state, symbol) in self.states.iter().zip(&self.symbols) {
+            if !symbol.is_empty() {
+                stack.push_str(symbol);
+                stack.push(' ');
+            }
+            stack.push_str(&state.to_string());
+            stack.push(' ');
+        }
+        stack.pop();
+
+        stack
+    }
+
+    /// The token on the input, with its class when the lexeme differs.
+    pub fn input(&self) -> String {
+        let class = Class::to_str(self.token.class.clone());
+
+        match &self.token.lexeme {
+            Some(lexeme) if *lexeme != class => format!("{} ({})", lexeme, class),
+            _ => class,
+        }
+    }
+
+    /// The action, followed by the rule of a reduction: `R5 (LV -> varfim pt_v)`.
+    pub fn action(&self) -> String {
+        match &self.rule {
+            Some(rule) => format!("{} ({})", self.action, rule),
+            None => self.action.to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TraceFormat {
+    Table,
+    Csv,
+    Html,
+}
+
+impl TraceFormat {
+    pub fn from_name(name: &str) -> Option<TraceFormat> {
+        match name {
+            "table" | "tabela" => Some(TraceFormat::Table),
+            "csv" => Some(TraceFormat::Csv),
+            "html" => Some(TraceFormat::Html),
+            _ => None,
+        }
+    }
+}
+
+/// Writes the steps one per row: an aligned table, CSV with a header or a
+/// standalone HTML page.
+pub fn write_trace<W: Write>(steps: &[Step], format: TraceFormat, out: &mut W) -> io::Result<()> {
+    match format {
+        TraceFormat::Table => {
+            let width = steps
+                .iter()
+                .map(|step| step.stack().chars().count())
+                .max()
+                .unwrap_or(0)
+                .max(5);
+            let input = steps
+                .iter()
+                .map(|step| step.input().chars().count())
+                .max()
+                .unwrap_or(0)
+                .max(7);
+
+            writeln!(
+                out,
+                "{:>5}  {:<width$}  {:<input$}  Ação",
+                "Passo", "Pilha", "Entrada"
+            )?;
+            for (n, step) in steps.iter().enumerate() {
+                writeln!(
+                    out,
+                    "{:>5}  {:<width$}  {:<input$}  {}",
+                    n + 1,
+                    step.stack(),
+                    step.input(),
+                    step.action()
+                )?;
+            }
+        }
+        TraceFormat::Csv => {
+            writeln!(out, "passo,pilha,entrada,acao")?;
+            for (n, step) in steps.iter().enumerate() {
+                writeln!(
+                    out,
+                    "{},{},{},{}",
+                    n + 1,
+                    csv_field(&step.stack()),
+                    csv_field(&step.input()),
+                    csv_field(&step.action())
+                )?;
+            }
+        }
+        TraceFormat::Html => {
+            writeln!(out, "<!DOCTYPE html>")?;
+            writeln!(out, "<html lang=\"pt-BR\">")?;
+            writeln!(out, "<head>")?;
+            writeln!(out, "<meta charset=\"utf-8\">")?;
+            writeln!(out, "<title>Análise sintática passo a passo</title>")?;
+            writeln!(
+                out,
+                "<style>table {{ border-collapse: collapse; font-family: monospace; }} th, td {{ border: 1px solid #999; padding: 2px 8px; text-align: left; }} tr.erro {{ background: #fdd; }}</style>"
+            )?;
+            writeln!(out, "</head>")?;
+            writeln!(out, "<body>")?;
+            writeln!(out, "<table>")?;
+            writeln!(
+                out,
+                "<tr><th>Passo</th><th>Pilha</th><th>Entrada</th><th>Ação</th></tr>"
+            )?;
+            for (n, step) in steps.iter().enumerate() {
+                let class = match step.action {
+                    SLRAction::E(_) => " class=\"erro\"",
+                    _ => "",
+                };
+                writeln!(
+                    out,
+                    "<tr{}><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                    class,
+                    n + 1,
+                    html_text(&step.stack()),
+                    html_text(&step.input()),
+                    html_text(&step.action())
+                )?;
+            }
+            writeln!(out, "</table>")?;
+            writeln!(out, "</body>")?;
+            writeln!(out, "</html>")?;
+        }
+    }
+
+    Ok(())
+}
+
+fn html_text(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,140 @@
+use analisador_lexico::{
+    ast::{Argument, Command, Condition, Expression, Operand, Program},
+    parser::Parser,
+    scanner::Scanner,
+    token::Token,
+};
+
+const SOURCE: &str = "inicio
+varinicio
+inteiro A, B;
+real C;
+varfim;
+leia A;
+B <- A + 1;
+se (A > B)
+entao
+escreva \"maior\";
+fimse
+repita (B < 10)
+B <- B * 2;
+fimrepita
+escreva C;
+fim
+";
+
+fn lexeme(token: &Token) -> &str {
+    token.lexeme.as_deref().unwrap_or("")
+}
+
+fn operand(operand: &Operand) -> String {
+    match operand {
+        Operand::Id(token) => format!("id {}", lexeme(token)),
+        Operand::Number(token) => format!("num {}", lexeme(token)),
+    }
+}
+
+fn condition(condition: &Condition) -> String {
+    format!(
+        "{} {} {}",
+        operand(&condition.left),
+        lexeme(&condition.operator),
+        operand(&condition.right)
+    )
+}
+
+/// The commands in a compact form, one per element, nested bodies in
+/// brackets.
+fn commands(list: &[Command]) -> Vec<String> {
+    list.iter()
+        .map(|command| match command {
+            Command::Read { id, .. } => format!("leia {}", lexeme(id)),
+            Command::Write { argument, .. } => match argument {
+                Argument::Literal(token) => format!("escreva lit {}", lexeme(token)),
+                Argument::Number(token) => format!("escreva num {}", lexeme(token)),
+                Argument::Id(token) => format!("escreva id {}", lexeme(token)),
+            },
+            Command::Assign { id, value, .. } => match value {
+                Expression::Operand(value) => format!("{} <- {}", lexeme(id), operand(value)),
+                Expression::Arithmetic {
+                    left,
+                    operator,
+                    right,
+                } => format!(
+                    "{} <- {} {} {}",
+                    lexeme(id),
+                    operand(left),
+                    lexeme(operator),
+                    operand(right)
+                ),
+            },
+            Command::If {
+                condition: test,
+                body,
+                ..
+            } => format!("se ({}) {:?}", condition(test), commands(body)),
+            Command::Repeat {
+                condition: test,
+                body,
+                ..
+            } => format!("repita ({}) {:?}", condition(test), commands(body)),
+        })
+        .collect()
+}
+
+fn parse(source: &str) -> Program {
+    let mut parser = Parser::new();
+    let program = parser.parse(&mut Scanner::from_str(source)).unwrap();
+    assert!(parser.diagnostics().is_empty());
+    program
+}
+
+#[test]
+fn declarations_keep_their_type_and_identifiers_in_order() {
+    let program = parse(SOURCE);
+
+    let declarations: Vec<(&str, Vec<&str>)> = program
+        .declarations
+        .iter()
+        .map(|declaration| {
+            (
+                lexeme(&declaration.data_type),
+                declaration.ids.iter().map(lexeme).collect(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        declarations,
+        [("inteiro", vec!["A", "B"]), ("real", vec!["C"])]
+    );
+}
+
+#[test]
+fn commands_nest_in_source_order() {
+    let program = parse(SOURCE);
+
+    assert_eq!(
+        commands(&program.commands),
+        [
+            "leia A",
+            "B <- id A + num 1",
+            "se (id A > id B) [\"escreva lit \\\"maior\\\"\"]",
+            "repita (id B < num 10) [\"B <- id B * num 2\"]",
+            "escreva id C",
+        ]
+    );
+}
+
+#[test]
+fn spans_point_at_the_source() {
+    let program = parse(SOURCE);
+
+    match &program.commands[1] {
+        Command::Assign { id, value, .. } => {
+            assert_eq!((id.span.line, id.span.column), (7, 1));
+            let span = value.span();
+            assert_eq!(&SOURCE[span.start..span.end], "A + 1");
+        }
+        command => panic!("esperada uma atribuição: {:?}", command),
+    }
+}
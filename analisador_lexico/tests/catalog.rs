@@ -0,0 +1,154 @@
+use std::collections::BTreeSet;
+
+use analisador_lexico::{
+    catalog::{self, Locale, Text},
+    diagnostic::{Code, Phase},
+};
+
+/// A position for every code. The `match` has no wildcard, so a code added
+/// to the enum without being listed here does not compile.
+fn position(code: Code) -> usize {
+    match code {
+        Code::EL0 => 0,
+        Code::EL1 => 1,
+        Code::EL2 => 2,
+        Code::EL3 => 3,
+        Code::EL4 => 4,
+        Code::EL5 => 5,
+        Code::EL6 => 6,
+        Code::EL7 => 7,
+        Code::ES0 => 8,
+        Code::ES1 => 9,
+        Code::ES2 => 10,
+        Code::ES3 => 11,
+        Code::ES4 => 12,
+        Code::ES5 => 13,
+        Code::ES6 => 14,
+        Code::ES7 => 15,
+        Code::ES8 => 16,
+        Code::ES9 => 17,
+        Code::ES10 => 18,
+        Code::ES11 => 19,
+        Code::EM1 => 20,
+        Code::EM2 => 21,
+        Code::EM3 => 22,
+        Code::EM4 => 23,
+        Code::EM5 => 24,
+    }
+}
+
+/// Every text of the catalog, the messages of `Code::ALL` first.
+fn texts() -> Vec<Text> {
+    let mut texts: Vec<Text> = Code::ALL.iter().map(|&code| Text::Message(code)).collect();
+    texts.extend([
+        Text::InThisNumber,
+        Text::FirstDeclaredHere,
+        Text::DeclaredAs,
+        Text::HasTypeLiteral,
+        Text::ProgramEndsHere,
+        Text::Skipped,
+        Text::DeclareBetween,
+        Text::Expected,
+        Text::TokenRemoved,
+        Text::Inserted,
+        Text::Replaced,
+        Text::Unrecoverable,
+        Text::Resumed,
+        Text::RemoveCharacter,
+        Text::CloseWith,
+        Text::RemoveDeclaration,
+        Text::Insert,
+        Text::RemoveRepeated,
+        Text::Remove,
+        Text::Replace,
+        Text::Error,
+        Text::Warning,
+        Text::Note,
+        Text::Help,
+        Text::NoErrors,
+        Text::OneError,
+        Text::ManyErrors,
+    ]);
+    for phase in [Phase::Lexical, Phase::Syntactic, Phase::Semantic] {
+        texts.extend([
+            Text::ErrorAt(phase),
+            Text::OneErrorIn(phase),
+            Text::ManyErrorsIn(phase),
+        ]);
+    }
+
+    texts
+}
+
+/// The `{n}` placeholders of a template.
+fn placeholders(template: &str) -> BTreeSet<usize> {
+    let mut found = BTreeSet::new();
+    let mut rest = template;
+
+    while let Some(open) = rest.find('{') {
+        rest = &rest[open + 1..];
+        if let Some(close) = rest.find('}') {
+            if let Ok(n) = rest[..close].parse::<usize>() {
+                found.insert(n);
+            }
+        }
+    }
+
+    found
+}
+
+#[test]
+fn code_all_lists_every_code_once() {
+    let mut positions: Vec<usize> = Code::ALL.iter().map(|&code| position(code)).collect();
+    positions.sort();
+
+    assert_eq!(positions, (0..Code::ALL.len()).collect::<Vec<usize>>());
+}
+
+#[test]
+fn every_code_has_a_message_in_every_locale() {
+    for code in Code::ALL {
+        for locale in Locale::ALL {
+            let message = catalog::template(locale, Text::Message(code));
+            assert!(
+                !message.trim().is_empty(),
+                "{:?} não tem mensagem em {}",
+                code,
+                Locale::to_str(locale)
+            );
+        }
+    }
+}
+
+#[test]
+fn every_locale_uses_the_same_placeholders() {
+    for text in texts() {
+        let expected = placeholders(catalog::template(Locale::PtBr, text));
+
+        for locale in Locale::ALL {
+            let template = catalog::template(locale, text);
+            assert!(!template.trim().is_empty(), "{:?} está vazio", text);
+            assert_eq!(
+                placeholders(template),
+                expected,
+                "{:?} em {}: {}",
+                text,
+                Locale::to_str(locale),
+                template
+            );
+        }
+    }
+}
+
+#[test]
+fn placeholders_are_numbered_from_zero() {
+    for text in texts() {
+        let found = placeholders(catalog::template(Locale::PtBr, text));
+        assert_eq!(
+            found,
+            (0..found.len()).collect::<BTreeSet<usize>>(),
+            "{:?}",
+            text
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use std::{
+    env, fs,
+    path::PathBuf,
+    process::{Command, Output},
+};
+
+const CLEAN: &str = "inicio
+varinicio
+inteiro A;
+varfim;
+leia A;
+escreva A;
+fim
+";
+
+const WITH_ERRORS: &str = "inicio
+varinicio
+inteiro A;
+varfim;
+leia B;
+escreva A
+fim
+";
+
+/// Writes `source` to a file of its own, so tests can run in parallel.
+fn program(name: &str, source: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!("mgol-cli-{}-{}.mgol", name, std::process::id()));
+    fs::write(&path, source).unwrap();
+    path
+}
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_analisador_lexico"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+#[test]
+fn clean_programs_exit_with_zero() {
+    let path = program("limpo", CLEAN);
+    let path = path.to_str().unwrap();
+
+    for command in [
+        "parse", "check", "symbols", "trace", "tree", "emit-c", "lex",
+    ] {
+        let output = run(&[command, path, "--color", "never"]);
+        assert_eq!(
+            output.status.code(),
+            Some(0),
+            "{}: {}",
+            command,
+            stderr(&output)
+        );
+    }
+    let output = run(&["check", path, "--color", "never"]);
+    assert_eq!(stdout(&output), "Nenhum erro encontrado\n");
+
+    fs::remove_file(path).ok();
+}
+
+#[test]
+fn programs_with_errors_exit_with_one() {
+    let path = program("erros", WITH_ERRORS);
+    let path = path.to_str().unwrap();
+
+    for format in ["human", "short", "json", "sarif"] {
+        let output = run(&["check", path, "--format", format, "--color", "never"]);
+        assert_eq!(output.status.code(), Some(1), "{}", format);
+        assert!(
+            stderr(&output).is_empty(),
+            "{}: {}",
+            format,
+            stderr(&output)
+        );
+    }
+    for command in ["parse", "symbols", "trace", "tree", "emit-c", "run"] {
+        let output = run(&[command, path, "--color", "never"]);
+        assert_eq!(output.status.code(), Some(1), "{}", command);
+    }
+    fs::remove_file(path).ok();
+
+    // The semantic error alone does not fail `parse`, which stops before it.
+    let path = program(
+        "semantico",
+        &WITH_ERRORS.replace("escreva A\n", "escreva A;\n"),
+    );
+    let path = path.to_str().unwrap();
+    assert_eq!(run(&["parse", path]).status.code(), Some(0));
+    assert_eq!(run(&["check", path]).status.code(), Some(1));
+
+    fs::remove_file(path).ok();
+}
+
+#[test]
+fn diagnostics_come_in_the_format_asked_for() {
+    let path = program("formatos", WITH_ERRORS);
+    let path = path.to_str().unwrap();
+
+    let human = stdout(&run(&["check", path, "--color", "never"]));
+    assert!(
+        human.starts_with("erro[EM1]: a variável 'B' não foi declarada\n"),
+        "{}",
+        human
+    );
+    assert!(human.ends_with("Foram encontrados 2 erros\n"), "{}", human);
+
+    let short = stdout(&run(&["check", path, "--format", "short"]));
+    assert!(short.contains("# ERRO 1\n"), "{}", short);
+
+    let json = stdout(&run(&["check", path, "--format", "json"]));
+    assert!(json.starts_with("{\"erros\":2,"), "{}", json);
+
+    let sarif = stdout(&run(&["check", path, "--format", "sarif"]));
+    assert!(sarif.contains("\"version\":\"2.1.0\""), "{}", sarif);
+
+    let symbols = stdout(&run(&["symbols", path, "--format", "json"]));
+    assert!(
+        symbols.starts_with('[') && symbols.contains("{\"lexema\":\"A\","),
+        "{}",
+        symbols
+    );
+
+    fs::remove_file(path).ok();
+}
+
+#[test]
+fn usage_errors_exit_with_two() {
+    let path = program("uso", CLEAN);
+    let path = path.to_str().unwrap();
+
+    for args in [
+        &["symbols", path, "--format", "sarif"][..],
+        &["symbols", path, "--format", "short"],
+        &["check", path, "--format", "dot"],
+        &["tree", path, "--format", "sarif"],
+        &["trace", path, "--format", "json"],
+        &["grammar", "--format", "json"],
+        &["check", path, "--color", "sometimes"],
+        &["check"],
+        &["check", "/nao/existe.mgol"],
+        &["compile", path],
+    ] {
+        let output = run(args);
+        assert_eq!(output.status.code(), Some(2), "{:?}", args);
+        assert!(stdout(&output).is_empty(), "{:?}", args);
+        assert!(
+            stderr(&output).starts_with("Erro: "),
+            "{:?}: {}",
+            args,
+            stderr(&output)
+        );
+    }
+
+    let output = run(&["symbols", path, "--format", "sarif"]);
+    assert_eq!(
+        stderr(&output),
+        "Erro: o comando symbols não aceita o formato 'sarif'\n"
+    );
+
+    fs::remove_file(path).ok();
+}
@@ -0,0 +1,219 @@
+use std::{
+    env, fs,
+    io::{self, Write},
+    path::PathBuf,
+    process::{Command, Stdio},
+};
+
+use analisador_lexico::{codegen, interpreter::Interpreter, parser::Parser, scanner::Scanner};
+
+/// Translates `source` with `emit-c`, compiles it with the system `cc` and
+/// runs it with `input` on stdin. `None` when there is no `cc`.
+fn compile_and_run(name: &str, source: &str, input: &str) -> Option<String> {
+    let dir = env::temp_dir().join(format!("mgol-codegen-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let mgol = dir.join("programa.mgol");
+    let c = dir.join("programa.c");
+    let binary: PathBuf = dir.join("programa");
+    fs::write(&mgol, source).unwrap();
+
+    let emitted = Command::new(env!("CARGO_BIN_EXE_analisador_lexico"))
+        .args(["emit-c", mgol.to_str().unwrap(), "-o", c.to_str().unwrap()])
+        .output()
+        .unwrap();
+    assert!(
+        emitted.status.success(),
+        "emit-c falhou: {}",
+        String::from_utf8_lossy(&emitted.stderr)
+    );
+
+    let compiled = match Command::new("cc").arg("-o").arg(&binary).arg(&c).output() {
+        Ok(compiled) => compiled,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            eprintln!("cc não encontrado, teste ignorado");
+            return None;
+        }
+        Err(err) => panic!("não foi possível executar cc: {}", err),
+    };
+    assert!(
+        compiled.status.success(),
+        "cc falhou:\n{}\n{}",
+        String::from_utf8_lossy(&compiled.stderr),
+        fs::read_to_string(&c).unwrap()
+    );
+
+    let mut child = Command::new(&binary)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success());
+
+    fs::remove_dir_all(&dir).ok();
+    Some(String::from_utf8(output.stdout).unwrap())
+}
+
+/// The C translation of `source`.
+fn translate(source: &str) -> String {
+    codegen::translate(&mut Scanner::from_str(source)).unwrap()
+}
+
+#[test]
+fn temporaries_are_t_and_names_that_collide_get_a_suffix() {
+    let c = translate(
+        "inicio
+varinicio
+inteiro T0, T0_, T1x, int, linha;
+varfim;
+T0 <- T1x + 1;
+T0_ <- T0 * 2;
+int <- linha;
+fim
+",
+    );
+
+    assert!(c.contains("    int T0;\n    int T1;\n"), "{}", c);
+    assert!(
+        c.contains("    int T0_ = 0;\n    int T0__ = 0;\n    int T1x = 0;\n"),
+        "{}",
+        c
+    );
+    assert!(
+        c.contains("    int int_ = 0;\n    int linha_ = 0;\n"),
+        "{}",
+        c
+    );
+    assert!(
+        c.contains("    T0 = T1x + 1;\n    T0_ = T0;\n    T1 = T0_ * 2;\n    T0__ = T1;\n"),
+        "{}",
+        c
+    );
+    assert!(c.contains("    int_ = linha_;\n"), "{}", c);
+}
+
+#[test]
+fn names_never_collide_with_temporaries_or_c() {
+    let source = "inicio
+varinicio
+inteiro T0, T0_, x, int, main, EOF, printf, linha;
+varfim;
+x <- x + 1;
+int <- x + 2;
+main <- int * 2;
+T0 <- main + 1;
+T0_ <- T0 + 1;
+EOF <- T0_ - 3;
+printf <- EOF;
+leia linha;
+escreva printf;
+escreva linha;
+fim
+";
+
+    if let Some(stdout) = compile_and_run("nomes", source, "7\n") {
+        assert_eq!(stdout, "57");
+    }
+}
+
+#[test]
+fn integers_with_leading_zeros_are_decimal() {
+    let source = "inicio
+varinicio
+inteiro a, b;
+varfim;
+a <- 010;
+b <- 09;
+escreva a;
+escreva \" \";
+escreva b;
+fim
+";
+
+    if let Some(stdout) = compile_and_run("zeros", source, "") {
+        assert_eq!(stdout, "10 9");
+    }
+}
+
+#[test]
+fn variables_start_at_zero_as_in_the_interpreter() {
+    let source = "inicio
+varinicio
+inteiro a;
+real r;
+literal s;
+varfim;
+escreva a;
+escreva r;
+escreva s;
+escreva \"fim\";
+fim
+";
+
+    if let Some(stdout) = compile_and_run("zero", source, "") {
+        assert_eq!(stdout, "00.000000fim");
+    }
+}
+
+#[test]
+fn reads_and_loops() {
+    let source = "inicio
+varinicio
+inteiro n, i;
+varfim;
+leia n;
+i <- 0;
+repita (i < n)
+escreva i;
+i <- i + 1;
+fimrepita
+se (n >= 3)
+entao
+escreva \"!\";
+fimse
+fim
+";
+
+    if let Some(stdout) = compile_and_run("leitura", source, "3\n") {
+        assert_eq!(stdout, "012!");
+    }
+}
+
+#[test]
+fn literals_are_read_as_whole_lines_as_in_the_interpreter() {
+    let source = "inicio
+varinicio
+inteiro n;
+literal s, t;
+varfim;
+leia n;
+leia s;
+leia t;
+escreva s;
+escreva \"-\";
+escreva t;
+escreva \"-\";
+escreva n;
+fim
+";
+    let input = "3\nola mundo\r\n  dois  espacos\n";
+
+    let mut scanner = Scanner::from_str(source);
+    let mut parser = Parser::new();
+    let program = parser.parse(&mut scanner).unwrap();
+    assert_eq!(parser.error_count(), 0);
+    let mut interpreter = Interpreter::new(input.as_bytes(), Vec::new());
+    interpreter.run(&program).unwrap();
+    let interpreted = String::from_utf8(interpreter.output().clone()).unwrap();
+    assert_eq!(interpreted, "ola mundo-  dois  espacos-3");
+
+    if let Some(stdout) = compile_and_run("linhas", source, input) {
+        assert_eq!(stdout, interpreted);
+    }
+}
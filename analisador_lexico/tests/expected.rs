@@ -0,0 +1,32 @@
+use analisador_lexico::{catalog::Locale, class::Class, parser::Parser, scanner::Scanner};
+
+#[test]
+fn expected_relational_operators_are_those_the_scanner_reads() {
+    let source = "inicio
+varinicio
+inteiro A;
+varfim;
+se (A A)
+entao
+escreva A;
+fimse
+fim
+";
+    let mut parser = Parser::new();
+    parser.parse(&mut Scanner::from_str(source));
+
+    let notes: Vec<String> = parser
+        .diagnostics()
+        .iter()
+        .next()
+        .unwrap()
+        .notes
+        .iter()
+        .map(|note| note.render(Locale::PtBr))
+        .collect();
+    assert_eq!(notes[0], "esperado: '<', '>', '<=', '>=', '<>'");
+
+    // A lone '=' is read as an assignment, never as a relational operator.
+    let token = Scanner::from_str("=").next().unwrap();
+    assert_eq!(token.class, Some(Class::Rcb));
+}
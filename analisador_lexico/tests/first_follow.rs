@@ -0,0 +1,55 @@
+use std::collections::BTreeSet;
+
+use analisador_lexico::{first_follow::FirstFollow, grammar::Grammar};
+
+/// The expression grammar without left recursion, as in the Dragon Book.
+const EXPRESSIONS: &str = "%token mais vezes abre fecha id
+%start E
+E -> T X
+X -> mais T X | ε
+T -> F Y
+Y -> vezes F Y | ε
+F -> abre E fecha | id
+";
+
+fn set(symbols: &[&str]) -> BTreeSet<String> {
+    symbols.iter().map(|symbol| String::from(*symbol)).collect()
+}
+
+#[test]
+fn first_and_follow_of_the_expression_grammar() {
+    let grammar = Grammar::from_source(EXPRESSIONS).unwrap();
+    let sets = FirstFollow::new(&grammar);
+
+    assert_eq!(sets.nullable, set(&["X", "Y"]));
+
+    for (non_terminal, first) in [
+        ("E", &["abre", "id"][..]),
+        ("T", &["abre", "id"]),
+        ("F", &["abre", "id"]),
+        ("X", &["mais"]),
+        ("Y", &["vezes"]),
+    ] {
+        assert_eq!(
+            sets.first[non_terminal],
+            set(first),
+            "FIRST({})",
+            non_terminal
+        );
+    }
+
+    for (non_terminal, follow) in [
+        ("E", &["eof", "fecha"][..]),
+        ("X", &["eof", "fecha"]),
+        ("T", &["eof", "fecha", "mais"]),
+        ("Y", &["eof", "fecha", "mais"]),
+        ("F", &["eof", "fecha", "mais", "vezes"]),
+    ] {
+        assert_eq!(
+            sets.follow(non_terminal),
+            set(follow),
+            "FOLLOW({})",
+            non_terminal
+        );
+    }
+}
@@ -0,0 +1,176 @@
+use analisador_lexico::{
+    catalog::Locale,
+    diagnostic::{Code, Diagnostic, Phase},
+    json::{self, Value},
+    parser::Parser,
+    sarif,
+    scanner::Scanner,
+};
+
+const LEXICAL: &str = "inicio
+varinicio
+inteiro A;
+varfim;
+A <- 1 @ 2;
+fim
+";
+
+const SYNTACTIC: &str = "inicio
+varinicio
+inteiro A;
+varfim;
+A <- 1
+escreva A;
+fim
+";
+
+const SEMANTIC: &str = "inicio
+varinicio
+inteiro A;
+varfim;
+B <- A;
+fim
+";
+
+/// Every sample with the phase and the code of its first diagnostic.
+const SAMPLES: [(&str, Phase, Code); 3] = [
+    (LEXICAL, Phase::Lexical, Code::EL0),
+    (SYNTACTIC, Phase::Syntactic, Code::ES9),
+    (SEMANTIC, Phase::Semantic, Code::EM1),
+];
+
+fn diagnostics(source: &str) -> Vec<Diagnostic> {
+    let mut parser = Parser::new();
+    parser.parse(&mut Scanner::from_str(source));
+    parser.diagnostics().iter().cloned().collect()
+}
+
+fn usize_at(value: &Value, keys: &[&str]) -> usize {
+    value
+        .at(keys)
+        .and_then(Value::as_usize)
+        .unwrap_or_else(|| panic!("{:?} ausente em {:?}", keys, value))
+}
+
+fn str_at<'a>(value: &'a Value, keys: &[&str]) -> &'a str {
+    value
+        .at(keys)
+        .and_then(Value::as_str)
+        .unwrap_or_else(|| panic!("{:?} ausente em {:?}", keys, value))
+}
+
+#[test]
+fn sarif_logs_follow_the_schema() {
+    for (source, phase, code) in SAMPLES {
+        let diagnostics = diagnostics(source);
+        let diagnostics: Vec<&Diagnostic> = diagnostics.iter().collect();
+        assert_eq!(diagnostics[0].code, code);
+
+        for locale in Locale::ALL {
+            let log =
+                Value::parse(&sarif::log(&diagnostics, locale, "dir/programa 1.mgol")).unwrap();
+            assert_eq!(str_at(&log, &["version"]), "2.1.0");
+            assert!(str_at(&log, &["$schema"]).contains("sarif-2.1.0"));
+
+            let runs = log.get("runs").and_then(Value::as_array).unwrap();
+            assert_eq!(runs.len(), 1);
+            let run = &runs[0];
+            assert_eq!(
+                str_at(run, &["tool", "driver", "name"]),
+                "analisador_lexico"
+            );
+
+            let rules = run
+                .at(&["tool", "driver", "rules"])
+                .and_then(Value::as_array)
+                .unwrap();
+            assert_eq!(rules.len(), Code::ALL.len());
+            for (rule, code) in rules.iter().zip(Code::ALL) {
+                assert_eq!(str_at(rule, &["id"]), Code::to_str(code));
+                assert!(!str_at(rule, &["shortDescription", "text"]).is_empty());
+                assert!(!str_at(rule, &["messageStrings", "default", "text"]).is_empty());
+                assert_eq!(
+                    str_at(rule, &["properties", "phase"]),
+                    Phase::to_str(Code::phase(code))
+                );
+            }
+
+            let results = run.get("results").and_then(Value::as_array).unwrap();
+            assert_eq!(results.len(), diagnostics.len());
+            for (result, diagnostic) in results.iter().zip(&diagnostics) {
+                let rule_id = str_at(result, &["ruleId"]);
+                assert_eq!(rule_id, Code::to_str(diagnostic.code));
+                assert_eq!(
+                    str_at(&rules[usize_at(result, &["ruleIndex"])], &["id"]),
+                    rule_id
+                );
+                assert_eq!(str_at(result, &["level"]), "error");
+                assert!(!str_at(result, &["message", "text"]).is_empty());
+
+                let locations = result.get("locations").and_then(Value::as_array).unwrap();
+                assert_eq!(locations.len(), 1);
+                let location = locations[0].get("physicalLocation").unwrap();
+                assert_eq!(
+                    str_at(location, &["artifactLocation", "uri"]),
+                    "dir/programa%201.mgol"
+                );
+                let region = location.get("region").unwrap();
+                let span = diagnostic.span;
+                assert_eq!(usize_at(region, &["startLine"]), span.line);
+                assert_eq!(usize_at(region, &["startColumn"]), span.column);
+                assert_eq!(usize_at(region, &["endLine"]), span.end_line);
+                assert_eq!(usize_at(region, &["endColumn"]), span.end_column);
+                assert_eq!(usize_at(region, &["byteOffset"]), span.start);
+                assert_eq!(usize_at(region, &["byteLength"]), span.len());
+            }
+
+            let first = &results[0];
+            assert_eq!(str_at(first, &["ruleId"]), Code::to_str(code));
+            let index = usize_at(first, &["ruleIndex"]);
+            assert_eq!(
+                str_at(&rules[index], &["properties", "phase"]),
+                Phase::to_str(phase)
+            );
+        }
+    }
+}
+
+#[test]
+fn json_diagnostics_follow_the_schema() {
+    for (source, phase, code) in SAMPLES {
+        let diagnostics = diagnostics(source);
+        let diagnostics: Vec<&Diagnostic> = diagnostics.iter().collect();
+
+        for locale in Locale::ALL {
+            let output = Value::parse(&json::diagnostics(&diagnostics, locale)).unwrap();
+            assert_eq!(usize_at(&output, &["erros"]), diagnostics.len());
+
+            let objects = output
+                .get("diagnosticos")
+                .and_then(Value::as_array)
+                .unwrap();
+            assert_eq!(objects.len(), diagnostics.len());
+            for (object, diagnostic) in objects.iter().zip(&diagnostics) {
+                assert_eq!(str_at(object, &["codigo"]), Code::to_str(diagnostic.code));
+                assert_eq!(str_at(object, &["fase"]), Phase::to_str(diagnostic.phase()));
+                assert_eq!(str_at(object, &["severidade"]), "error");
+                assert_eq!(str_at(object, &["mensagem"]), diagnostic.message(locale));
+
+                let span = diagnostic.span;
+                assert_eq!(usize_at(object, &["linha"]), span.line);
+                assert_eq!(usize_at(object, &["coluna"]), span.column);
+                assert_eq!(usize_at(object, &["linha_fim"]), span.end_line);
+                assert_eq!(usize_at(object, &["coluna_fim"]), span.end_column);
+                assert_eq!(usize_at(object, &["inicio"]), span.start);
+                assert_eq!(usize_at(object, &["fim"]), span.end);
+
+                for field in ["rotulos", "notas", "correcoes"] {
+                    assert!(object.get(field).and_then(Value::as_array).is_some());
+                }
+            }
+
+            assert_eq!(str_at(&objects[0], &["codigo"]), Code::to_str(code));
+            assert_eq!(str_at(&objects[0], &["fase"]), Phase::to_str(phase));
+        }
+    }
+}
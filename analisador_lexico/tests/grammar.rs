@@ -0,0 +1,101 @@
+use analisador_lexico::grammar::{Grammar, GrammarError};
+
+#[test]
+fn a_non_productive_start_is_reported_once() {
+    let errors = Grammar::from_source(
+        "%token a
+%start S
+S -> a S
+",
+    )
+    .err()
+    .unwrap();
+
+    assert_eq!(errors, [GrammarError::NonProductive(String::from("S"))]);
+}
+
+#[test]
+fn errors_never_name_the_augmented_start() {
+    let errors = Grammar::from_source(
+        "%token a b
+%start S
+S -> a A
+A -> A b
+B -> b
+",
+    )
+    .err()
+    .unwrap();
+
+    assert_eq!(
+        errors,
+        [
+            GrammarError::NonProductive(String::from("S")),
+            GrammarError::NonProductive(String::from("A")),
+            GrammarError::Unreachable(String::from("B")),
+        ]
+    );
+}
+
+#[test]
+fn the_mgol_grammar_has_no_errors() {
+    let source = include_str!("../src/mgol.bnf");
+
+    assert!(Grammar::from_source(source).is_ok());
+}
+
+#[test]
+fn directives_are_whole_words() {
+    let errors = Grammar::from_source(
+        "%tokens a
+%token a
+%startS
+S -> a
+",
+    )
+    .err()
+    .unwrap();
+
+    assert_eq!(
+        errors,
+        [
+            GrammarError::Syntax {
+                line: 1,
+                message: String::from("declaração desconhecida '%tokens a'"),
+            },
+            GrammarError::Syntax {
+                line: 3,
+                message: String::from("declaração desconhecida '%startS'"),
+            },
+        ]
+    );
+
+    let grammar = Grammar::from_source("%token\ta\n%start\tS\nS -> a\n").unwrap();
+    assert_eq!(grammar.terminals(), ["a", "eof"]);
+    assert_eq!(grammar.start(), "S'");
+}
+
+#[test]
+fn the_empty_word_must_be_written() {
+    let errors = Grammar::from_source(
+        "%token a b
+%start S
+S -> a A |
+A -> b || a
+  |
+B ->
+",
+    )
+    .err()
+    .unwrap();
+
+    let empty = |line| GrammarError::Syntax {
+        line,
+        message: String::from("alternativa vazia, use 'ε' para a palavra vazia"),
+    };
+    assert_eq!(errors, [empty(3), empty(4), empty(5), empty(6)]);
+
+    let grammar = Grammar::from_source("%token a\n%start S\nS -> a S | ε\n").unwrap();
+    assert_eq!(grammar.rules().len(), 3);
+    assert!(grammar.rules()[2].right.is_empty());
+}
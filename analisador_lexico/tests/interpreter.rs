@@ -0,0 +1,98 @@
+use analisador_lexico::{
+    interpreter::{Interpreter, RuntimeError},
+    parser::Parser,
+    scanner::Scanner,
+};
+
+/// `src/source_code.txt` without its second declaration of `B`, which is
+/// there to show a semantic error.
+const SOURCE_CODE: &str = r#"inicio
+    varinicio
+        literal A;
+        inteiro B;
+        inteiro D; 
+        real C;
+    varfim;
+    escreva "Digite B:"; 
+    leia B;
+    escreva "Digite A:";
+    leia A;
+    se(B>2)
+    entao
+        se(B<=4) 
+        entao
+            escreva "B esta entre 2 e 4"; 
+        fimse
+    fimse 
+    B<-B+1;
+    B<-B+2; 
+    B<-B+3; 
+    D<-B; 
+    C<-5.0;
+    repita (B<5)
+        C<-B+2; 
+        escreva C;
+        B<-B+1;
+    fimrepita
+    escreva "\nB=\n"; 
+    escreva D; 
+    escreva "\n"; 
+    escreva C; 
+    escreva "\n"; 
+    escreva A;
+fim"#;
+
+/// Parses `source`, which must have no errors, and runs it with `input` on
+/// stdin. Returns what it wrote and how it ended.
+fn run(source: &str, input: &[u8]) -> (String, Result<(), RuntimeError>) {
+    let mut parser = Parser::new();
+    let program = parser.parse(&mut Scanner::from_str(source)).unwrap();
+    assert!(parser.diagnostics().is_empty());
+
+    let mut interpreter = Interpreter::new(input, Vec::new());
+    let result = interpreter.run(&program);
+
+    (String::from_utf8(interpreter.output().clone()).unwrap(), result)
+}
+
+#[test]
+fn runs_source_code_with_canned_input() {
+    let (stdout, result) = run(SOURCE_CODE, &b"3\nfoo\n"[..]);
+
+    assert!(result.is_ok());
+    assert_eq!(
+        stdout,
+        "Digite B:Digite A:B esta entre 2 e 4\nB=\n9\n5.000000\nfoo"
+    );
+}
+
+#[test]
+fn repeats_while_the_condition_holds() {
+    let (stdout, result) = run(SOURCE_CODE, &b"-2\nbar\n"[..]);
+
+    assert!(result.is_ok());
+    assert_eq!(stdout, "Digite B:Digite A:6.000000\nB=\n4\n6.000000\nbar");
+}
+
+#[test]
+fn invalid_integer_is_a_runtime_error_on_its_line() {
+    let (stdout, result) = run(SOURCE_CODE, &b"x\n"[..]);
+
+    let error = result.unwrap_err();
+    assert_eq!(error.line, 9);
+    assert_eq!(error.message, "'x' não é um número inteiro válido para 'B'");
+    assert_eq!(
+        error.to_string(),
+        "Erro de execução na linha 9: 'x' não é um número inteiro válido para 'B'"
+    );
+    assert_eq!(stdout, "Digite B:");
+}
+
+#[test]
+fn end_of_input_during_leia_is_a_runtime_error() {
+    let (_, result) = run(SOURCE_CODE, &b"3\n"[..]);
+
+    let error = result.unwrap_err();
+    assert_eq!(error.line, 11);
+    assert_eq!(error.message, "fim da entrada durante um 'leia'");
+}
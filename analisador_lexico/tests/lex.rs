@@ -0,0 +1,94 @@
+use std::{
+    env, fs,
+    io::{self, BufReader, Read},
+    process::Command,
+};
+
+use analisador_lexico::{
+    class::Class,
+    diagnostic::{Code, Diagnostic},
+    scanner::Scanner,
+};
+
+#[test]
+fn error_tokens_keep_their_text_and_the_errors_go_to_stderr() {
+    let path = env::temp_dir().join(format!("mgol-lex-{}.mgol", std::process::id()));
+    fs::write(&path, "inicio @ 1.e fim").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_analisador_lexico"))
+        .args([
+            "lex",
+            path.to_str().unwrap(),
+            "--format",
+            "csv",
+            "--color",
+            "never",
+        ])
+        .output()
+        .unwrap();
+    fs::remove_file(&path).ok();
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let errors: Vec<&str> = stdout
+        .lines()
+        .filter(|line| line.starts_with("erro,"))
+        .map(|line| line.split(',').nth(1).unwrap())
+        .collect();
+    assert_eq!(errors, ["@", "1.e"]);
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("erro[EL0]"), "{}", stderr);
+    assert!(stderr.contains("erro[EL2]"), "{}", stderr);
+    assert_eq!(output.status.code(), Some(1));
+}
+
+/// The classes of the tokens `scanner` reads up to `eof`, and its
+/// diagnostics.
+fn scan<R: io::BufRead>(mut scanner: Scanner<R>) -> (Vec<Option<Class>>, Vec<Diagnostic>) {
+    let classes = scanner.by_ref().map(|token| token.class).collect();
+
+    (classes, scanner.diagnostics().iter().cloned().collect())
+}
+
+#[test]
+fn sources_that_are_not_utf8_stop_with_el7() {
+    let (classes, diagnostics) = scan(Scanner::from_reader(&b"inicio\nvarinicio \xff\nfim\n"[..]));
+
+    assert_eq!(classes, vec![Some(Class::Inicio), Some(Class::Eof)]);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, Code::EL7);
+    assert_eq!(
+        (diagnostics[0].span.line, diagnostics[0].span.column),
+        (2, 1)
+    );
+    assert_eq!(
+        diagnostics[0].args,
+        vec!["stream did not contain valid UTF-8"]
+    );
+}
+
+/// A reader that fails once `ok` has been read.
+struct Failing<'a> {
+    ok: &'a [u8],
+}
+
+impl Read for Failing<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self.ok.is_empty() {
+            true => Err(io::Error::other("disco removido")),
+            false => self.ok.read(buf),
+        }
+    }
+}
+
+#[test]
+fn read_errors_stop_with_el7() {
+    let (classes, diagnostics) = scan(Scanner::from_reader(BufReader::new(Failing {
+        ok: b"inicio\n",
+    })));
+
+    assert_eq!(classes, vec![Some(Class::Inicio), Some(Class::Eof)]);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code, Code::EL7);
+    assert_eq!(diagnostics[0].args, vec!["disco removido"]);
+}
@@ -0,0 +1,37 @@
+use analisador_lexico::{grammar::Grammar, lr0::LR0Automaton};
+
+/// The left-recursive expression grammar, whose LR(0) automaton in the
+/// Dragon Book has 12 states and 22 transitions.
+const EXPRESSIONS: &str = "%token mais vezes abre fecha id
+%start E
+E -> E mais T | T
+T -> T vezes F | F
+F -> abre E fecha | id
+";
+
+#[test]
+fn the_automaton_of_the_expression_grammar_is_exported_as_dot() {
+    let grammar = Grammar::from_source(EXPRESSIONS).unwrap();
+    let automaton = LR0Automaton::new(&grammar);
+    let dot = automaton.to_dot(&grammar);
+
+    assert_eq!(automaton.len(), 12);
+    assert!(dot.starts_with("digraph lr0 {\n"), "{}", dot);
+    assert!(dot.ends_with("}\n"), "{}", dot);
+
+    let nodes = dot
+        .lines()
+        .filter(|line| line.contains(" [label=\"I"))
+        .count();
+    let edges: Vec<&str> = dot.lines().filter(|line| line.contains(" -> s")).collect();
+    assert_eq!(nodes, 12);
+    assert_eq!(edges.len(), 22);
+
+    // Only goto edges, on the 3 nonterminals, are dashed.
+    let dashed = edges
+        .iter()
+        .filter(|edge| edge.contains("style=dashed"))
+        .count();
+    assert_eq!(dashed, 9);
+    assert_eq!(dot.matches("peripheries=2").count(), 1);
+}
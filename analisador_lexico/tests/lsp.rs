@@ -0,0 +1,325 @@
+use analisador_lexico::{
+    json::Value,
+    lsp::{self, Server, MAX_MESSAGE_LENGTH},
+};
+
+const URI: &str = "file:///programa.mgol";
+
+const PROGRAM: &str = "inicio
+varinicio
+inteiro A;
+real B;
+varfim;
+leia A;
+B <- A + 1;
+escreva C;
+fim
+";
+
+/// Sends `message` and parses every message the server answers with.
+fn send(server: &mut Server, message: &str) -> Vec<Value> {
+    server
+        .handle(message)
+        .iter()
+        .map(|response| Value::parse(response).unwrap())
+        .collect()
+}
+
+/// The result of a request, which must be answered with exactly one response.
+fn request(server: &mut Server, id: usize, method: &str, params: &str) -> Value {
+    let mut responses = send(
+        server,
+        &format!(
+            "{{\"jsonrpc\":\"2.0\",\"id\":{},\"method\":\"{}\",\"params\":{}}}",
+            id, method, params
+        ),
+    );
+    assert_eq!(responses.len(), 1);
+    let response = responses.remove(0);
+    assert_eq!(response.get("id").and_then(Value::as_usize), Some(id));
+
+    match response.get("result") {
+        Some(result) => result.clone(),
+        None => panic!("{} falhou: {}", method, response),
+    }
+}
+
+/// An initialized server with `PROGRAM` open, and the diagnostics published
+/// when it was opened.
+fn open() -> (Server, Value) {
+    let mut server = Server::new();
+    request(
+        &mut server,
+        1,
+        "initialize",
+        "{\"locale\":\"pt-BR\",\"capabilities\":{}}",
+    );
+    send(
+        &mut server,
+        "{\"jsonrpc\":\"2.0\",\"method\":\"initialized\",\"params\":{}}",
+    );
+
+    let did_open = Value::object(vec![
+        ("jsonrpc", Value::from("2.0")),
+        ("method", Value::from("textDocument/didOpen")),
+        (
+            "params",
+            Value::object(vec![(
+                "textDocument",
+                Value::object(vec![
+                    ("uri", Value::from(URI)),
+                    ("languageId", Value::from("mgol")),
+                    ("version", Value::from(1)),
+                    ("text", Value::from(PROGRAM)),
+                ]),
+            )]),
+        ),
+    ]);
+    let mut published = send(&mut server, &did_open.to_string());
+    assert_eq!(published.len(), 1);
+
+    (server, published.remove(0))
+}
+
+fn position(line: usize, character: usize) -> String {
+    format!(
+        "{{\"textDocument\":{{\"uri\":\"{}\"}},\"position\":{{\"line\":{},\"character\":{}}}}}",
+        URI, line, character
+    )
+}
+
+fn start(range: &Value) -> (usize, usize) {
+    (
+        range
+            .at(&["start", "line"])
+            .and_then(Value::as_usize)
+            .unwrap(),
+        range
+            .at(&["start", "character"])
+            .and_then(Value::as_usize)
+            .unwrap(),
+    )
+}
+
+#[test]
+fn initialize_announces_the_capabilities() {
+    let mut server = Server::new();
+    let result = request(&mut server, 1, "initialize", "{\"capabilities\":{}}");
+
+    let capabilities = result.get("capabilities").unwrap();
+    assert_eq!(
+        capabilities
+            .get("textDocumentSync")
+            .and_then(Value::as_usize),
+        Some(1)
+    );
+    for provider in [
+        "hoverProvider",
+        "definitionProvider",
+        "documentSymbolProvider",
+    ] {
+        assert_eq!(
+            capabilities.get(provider),
+            Some(&Value::Bool(true)),
+            "{}",
+            provider
+        );
+    }
+    assert!(capabilities.get("completionProvider").is_some());
+    assert_eq!(
+        result.at(&["serverInfo", "name"]).and_then(Value::as_str),
+        Some("mgol-lsp")
+    );
+}
+
+#[test]
+fn did_open_publishes_the_diagnostics() {
+    let (_, published) = open();
+
+    assert_eq!(
+        published.get("method").and_then(Value::as_str),
+        Some("textDocument/publishDiagnostics")
+    );
+    assert_eq!(
+        published.at(&["params", "uri"]).and_then(Value::as_str),
+        Some(URI)
+    );
+
+    let diagnostics = published
+        .at(&["params", "diagnostics"])
+        .and_then(Value::as_array)
+        .unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    let diagnostic = &diagnostics[0];
+    assert_eq!(diagnostic.get("code").and_then(Value::as_str), Some("EM1"));
+    assert_eq!(
+        diagnostic.get("severity").and_then(Value::as_usize),
+        Some(1)
+    );
+    assert_eq!(
+        diagnostic.get("source").and_then(Value::as_str),
+        Some("mgol")
+    );
+    assert!(diagnostic
+        .get("message")
+        .and_then(Value::as_str)
+        .unwrap()
+        .contains("'C'"));
+    assert_eq!(start(diagnostic.get("range").unwrap()), (7, 8));
+}
+
+#[test]
+fn hover_shows_the_declaration() {
+    let (mut server, _) = open();
+
+    let hover = request(&mut server, 2, "textDocument/hover", &position(6, 5));
+    assert_eq!(
+        hover.at(&["contents", "value"]).and_then(Value::as_str),
+        Some("```mgol\ninteiro A\n```")
+    );
+
+    let nothing = request(&mut server, 3, "textDocument/hover", &position(0, 2));
+    assert_eq!(nothing, Value::Null);
+}
+
+#[test]
+fn definition_goes_to_the_declaration() {
+    let (mut server, _) = open();
+
+    let location = request(&mut server, 2, "textDocument/definition", &position(6, 0));
+    assert_eq!(location.get("uri").and_then(Value::as_str), Some(URI));
+    assert_eq!(start(location.get("range").unwrap()), (3, 5));
+
+    let undeclared = request(&mut server, 3, "textDocument/definition", &position(7, 8));
+    assert_eq!(undeclared, Value::Null);
+}
+
+#[test]
+fn document_symbols_are_the_declared_variables() {
+    let (mut server, _) = open();
+    let params = format!("{{\"textDocument\":{{\"uri\":\"{}\"}}}}", URI);
+
+    let symbols = request(&mut server, 2, "textDocument/documentSymbol", &params);
+    let symbols: Vec<(&str, &str, (usize, usize))> = symbols
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|symbol| {
+            (
+                symbol.get("name").and_then(Value::as_str).unwrap(),
+                symbol.get("detail").and_then(Value::as_str).unwrap(),
+                start(symbol.get("range").unwrap()),
+            )
+        })
+        .collect();
+
+    assert_eq!(symbols, [("A", "inteiro", (2, 8)), ("B", "real", (3, 5))]);
+}
+
+#[test]
+fn completion_offers_keywords_and_variables() {
+    let (mut server, _) = open();
+    let params = position(8, 0);
+
+    let items = request(&mut server, 2, "textDocument/completion", &params);
+    let labels: Vec<&str> = items
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item.get("label").and_then(Value::as_str).unwrap())
+        .collect();
+
+    for label in ["inicio", "escreva", "fimrepita", "A", "B"] {
+        assert!(labels.contains(&label), "{} ausente de {:?}", label, labels);
+    }
+    assert!(!labels.contains(&"C"));
+}
+
+#[test]
+fn requests_after_shutdown_fail_and_exit_reports_it() {
+    let (mut server, _) = open();
+
+    let unknown = send(
+        &mut server,
+        "{\"jsonrpc\":\"2.0\",\"id\":2,\"method\":\"naoExiste\"}",
+    );
+    assert_eq!(
+        unknown[0].at(&["error", "code"]),
+        Some(&Value::Number(-32601.0))
+    );
+
+    assert_eq!(request(&mut server, 3, "shutdown", "null"), Value::Null);
+    let refused = send(
+        &mut server,
+        "{\"jsonrpc\":\"2.0\",\"id\":4,\"method\":\"textDocument/hover\"}",
+    );
+    assert_eq!(
+        refused[0].at(&["error", "code"]),
+        Some(&Value::Number(-32600.0))
+    );
+
+    assert!(send(&mut server, "{\"jsonrpc\":\"2.0\",\"method\":\"exit\"}").is_empty());
+    assert!(server.exited());
+    assert_eq!(server.exit_code(), 0);
+}
+
+#[test]
+fn deeply_nested_json_is_a_parse_error() {
+    let mut server = Server::new();
+
+    let responses = send(&mut server, &"[".repeat(200_000));
+    assert_eq!(responses.len(), 1);
+    assert_eq!(responses[0].get("id"), Some(&Value::Null));
+    assert_eq!(
+        responses[0].at(&["error", "code"]),
+        Some(&Value::Number(-32700.0))
+    );
+
+    let result = request(&mut server, 1, "initialize", "{\"capabilities\":{}}");
+    assert!(result.get("capabilities").is_some());
+}
+
+/// Every message `serve` writes for `input`, and its exit status.
+fn serve(input: &[u8]) -> (Vec<Value>, i32) {
+    let mut output: Vec<u8> = Vec::new();
+    let status = lsp::serve(input, &mut output).unwrap();
+
+    let mut reader = &output[..];
+    let mut messages = Vec::new();
+    while let Some(message) = lsp::read_message(&mut reader).unwrap() {
+        messages.push(Value::parse(&message.unwrap()).unwrap());
+    }
+
+    (messages, status)
+}
+
+fn frame(body: &str) -> Vec<u8> {
+    format!("Content-Length: {}\r\n\r\n{}", body.len(), body).into_bytes()
+}
+
+#[test]
+fn messages_over_the_limit_are_refused_and_skipped() {
+    let mut input = format!("Content-Length: {}\r\n\r\n", MAX_MESSAGE_LENGTH + 1).into_bytes();
+    input.extend(vec![b' '; MAX_MESSAGE_LENGTH + 1]);
+    input.extend(frame(
+        "{\"jsonrpc\":\"2.0\",\"id\":1,\"method\":\"shutdown\"}",
+    ));
+    input.extend(frame("{\"jsonrpc\":\"2.0\",\"method\":\"exit\"}"));
+
+    let (messages, status) = serve(&input);
+    assert_eq!(messages.len(), 2);
+    assert_eq!(
+        messages[0].at(&["error", "code"]),
+        Some(&Value::Number(-32600.0))
+    );
+    assert_eq!(messages[1].get("id").and_then(Value::as_usize), Some(1));
+    assert_eq!(status, 0);
+}
+
+#[test]
+fn a_huge_content_length_is_not_allocated() {
+    let (messages, status) = serve(b"Content-Length: 18446744073709551615\r\n\r\n{}");
+
+    assert!(messages.is_empty());
+    assert_eq!(status, 1);
+}
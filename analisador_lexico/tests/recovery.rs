@@ -0,0 +1,151 @@
+use analisador_lexico::{
+    catalog::Text,
+    diagnostic::{Code, Diagnostic},
+    parser::Parser,
+    scanner::Scanner,
+    span::Span,
+};
+
+/// The diagnostics of `commands` inside a program that declares `A` and `B`.
+fn diagnostics(commands: &str) -> Vec<Diagnostic> {
+    let source = format!(
+        "inicio\nvarinicio\ninteiro A, B;\nvarfim;\n{}fim\n",
+        commands
+    );
+    let mut parser = Parser::new();
+    parser.parse(&mut Scanner::from_str(&source));
+
+    parser.diagnostics().iter().cloned().collect()
+}
+
+fn codes(diagnostics: &[Diagnostic]) -> Vec<Code> {
+    diagnostics
+        .iter()
+        .map(|diagnostic| diagnostic.code)
+        .collect()
+}
+
+/// Line, column, end line and end column of `span`.
+fn position(span: &Span) -> (usize, usize, usize, usize) {
+    (span.line, span.column, span.end_line, span.end_column)
+}
+
+/// The span and the replacement of the only fix of `diagnostic`.
+fn fix(diagnostic: &Diagnostic) -> ((usize, usize, usize, usize), &str) {
+    assert_eq!(diagnostic.fixes.len(), 1, "{:?}", diagnostic);
+    let fix = &diagnostic.fixes[0];
+
+    (position(&fix.span), fix.replacement.as_str())
+}
+
+/// The token panic mode resumed at, from the note of `diagnostic`.
+fn resumed_at(diagnostic: &Diagnostic) -> &str {
+    let note = diagnostic
+        .notes
+        .iter()
+        .find(|note| note.text == Text::Resumed)
+        .unwrap();
+
+    &note.args[0]
+}
+
+#[test]
+fn each_statement_reports_its_own_error_without_a_cascade() {
+    let diagnostics = diagnostics(
+        "leia A
+se (A > B)
+escreva A;
+fimse
+B <- A + ;
+escreva B;
+",
+    );
+
+    assert_eq!(codes(&diagnostics), vec![Code::ES2, Code::ES9, Code::ES0]);
+    assert_eq!(position(&diagnostics[0].span), (5, 7, 5, 7));
+    assert_eq!(position(&diagnostics[1].span), (6, 11, 6, 11));
+    assert_eq!(diagnostics[1].args, vec!["entao", "escreva"]);
+    assert_eq!(position(&diagnostics[2].span), (9, 10, 9, 11));
+}
+
+#[test]
+fn insertions_are_fixed_at_the_end_of_the_previous_token() {
+    let diagnostics = diagnostics(
+        "leia A
+escreva A;
+se (A > 1 entao
+escreva A;
+fimse
+escreva A;
+se A > 1) entao
+escreva A;
+fimse
+",
+    );
+
+    assert_eq!(codes(&diagnostics), vec![Code::ES2, Code::ES9, Code::ES6]);
+    assert_eq!(fix(&diagnostics[0]), ((5, 7, 5, 7), ";"));
+    assert_eq!(fix(&diagnostics[1]), ((7, 10, 7, 10), ")"));
+    assert_eq!(fix(&diagnostics[2]), ((11, 3, 11, 3), "("));
+}
+
+#[test]
+fn deletions_and_replacements_are_fixed_over_the_token() {
+    let diagnostics = diagnostics(
+        "leia A;;
+escreva A;
+A <- A + 1 1;
+escreva A;
+leia A)
+escreva A;
+",
+    );
+
+    assert_eq!(codes(&diagnostics), vec![Code::ES3, Code::ES10, Code::ES11]);
+    assert_eq!(fix(&diagnostics[0]), ((5, 8, 5, 9), ""));
+    assert_eq!(fix(&diagnostics[1]), ((7, 12, 7, 13), ""));
+    assert_eq!(fix(&diagnostics[2]), ((9, 7, 9, 8), ";"));
+}
+
+#[test]
+fn panic_mode_resumes_after_the_statement() {
+    let diagnostics = diagnostics(
+        "se (A > 1) entao
+A <- + ;
+escreva A;
+A <- * fimse
+escreva A;
+",
+    );
+
+    assert_eq!(codes(&diagnostics), vec![Code::ES0, Code::ES0]);
+    assert!(diagnostics
+        .iter()
+        .all(|diagnostic| diagnostic.fixes.is_empty()));
+
+    assert_eq!(diagnostics[0].args, vec!["+"]);
+    assert_eq!(position(&diagnostics[0].labels[0].span), (6, 6, 6, 9));
+    assert_eq!(resumed_at(&diagnostics[0]), "escreva");
+
+    assert_eq!(diagnostics[1].args, vec!["*"]);
+    assert_eq!(position(&diagnostics[1].labels[0].span), (8, 6, 8, 7));
+    assert_eq!(resumed_at(&diagnostics[1]), "fimse");
+}
+
+#[test]
+fn parsing_stops_at_the_error_cap() {
+    let source = format!(
+        "inicio\nvarinicio\ninteiro A;\nvarfim;\n{}fim\n",
+        "A <- + A; escreva A; leia A;\n".repeat(150)
+    );
+    let mut parser = Parser::new();
+
+    for _ in 0..2 {
+        parser.parse(&mut Scanner::from_str(&source));
+        assert_eq!(parser.error_count(), 100);
+        assert!(parser
+            .diagnostics()
+            .iter()
+            .all(|diagnostic| diagnostic.code == Code::ES10));
+    }
+}
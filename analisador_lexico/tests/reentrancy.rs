@@ -0,0 +1,69 @@
+use std::{env, fs, fs::File, path::PathBuf, thread};
+
+use analisador_lexico::{parser::Parser, scanner::Scanner};
+
+const SOURCE_CODE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/source_code.txt");
+
+const SECOND: &str = "inicio
+varinicio
+inteiro n;
+varfim;
+leia n;
+repita (n > 0)
+escreva n;
+n <- n - 1;
+fimrepita
+fim
+";
+
+/// Every token of `path`, scanned from the file, and the number of commands
+/// of the program parsed from it.
+fn scan(path: &PathBuf) -> (Vec<String>, usize) {
+    let tokens: Vec<String> = Scanner::new(File::open(path).unwrap())
+        .map(|token| format!("{:?}", token))
+        .collect();
+    let program = Parser::new()
+        .parse(&mut Scanner::new(File::open(path).unwrap()))
+        .unwrap();
+
+    (tokens, program.commands.len())
+}
+
+#[test]
+fn scanning_two_files_gives_the_same_result_in_any_order_or_thread() {
+    let second = env::temp_dir().join(format!("mgol-reentrancy-{}.mgol", std::process::id()));
+    fs::write(&second, SECOND).unwrap();
+    let paths = [PathBuf::from(SOURCE_CODE), second.clone()];
+
+    let expected: Vec<(Vec<String>, usize)> = paths.iter().map(scan).collect();
+    assert!(expected
+        .iter()
+        .all(|(tokens, commands)| tokens.len() > 1 && *commands > 0));
+    assert_ne!(expected[0], expected[1]);
+
+    // One after the other, both ways round, the second scan starts where a
+    // fresh one would even though the first hit the end of its file.
+    for order in [[0, 1], [1, 0]] {
+        for i in order {
+            assert_eq!(scan(&paths[i]), expected[i]);
+        }
+    }
+
+    let concurrent: Vec<(usize, (Vec<String>, usize))> = thread::scope(|scope| {
+        let threads: Vec<_> = (0..8)
+            .map(|n| {
+                let path = &paths[n % 2];
+                scope.spawn(move || (n % 2, scan(path)))
+            })
+            .collect();
+        threads
+            .into_iter()
+            .map(|thread| thread.join().unwrap())
+            .collect()
+    });
+    for (i, result) in concurrent {
+        assert_eq!(result, expected[i]);
+    }
+
+    fs::remove_file(&second).ok();
+}
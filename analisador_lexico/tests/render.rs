@@ -0,0 +1,184 @@
+use std::{env, fs, process::Command};
+
+use analisador_lexico::{
+    catalog::Text,
+    diagnostic::{Code, Diagnostic, Message},
+    parser::Parser,
+    render::Renderer,
+    scanner::Scanner,
+    span::Span,
+};
+
+/// The first diagnostic of `source`, rendered without colour.
+fn first(source: &str) -> String {
+    let mut parser = Parser::new();
+    parser.parse(&mut Scanner::from_str(source));
+    let diagnostic = parser.diagnostics().iter().next().unwrap();
+
+    Renderer::new("teste.mgol", source).render(diagnostic)
+}
+
+/// The span of `source` from `line:column` to `end_line:end_column`.
+fn span(
+    source: &str,
+    (line, column): (usize, usize),
+    (end_line, end_column): (usize, usize),
+) -> Span {
+    let offset = |line: usize, column: usize| -> usize {
+        let start: usize = source
+            .split('\n')
+            .take(line - 1)
+            .map(|line| line.len() + 1)
+            .sum();
+        start + column - 1
+    };
+
+    Span {
+        start: offset(line, column),
+        end: offset(end_line, end_column),
+        line,
+        column,
+        end_line,
+        end_column,
+    }
+}
+
+#[test]
+fn one_annotation() {
+    assert_eq!(
+        first("inicio @ fim"),
+        "erro[EL0]: '@' não pertence ao alfabeto
+ --> teste.mgol:1:8
+  |
+1 | inicio @ fim
+  |        ^
+  |
+  = ajuda: remova o caractere '@'
+"
+    );
+}
+
+#[test]
+fn primary_with_a_label_on_another_line() {
+    let source = "inicio
+varinicio
+inteiro A;
+inteiro A;
+varfim;
+fim
+";
+
+    assert_eq!(
+        first(source),
+        "erro[EM2]: a variável 'A' já foi declarada
+ --> teste.mgol:4:9
+  |
+3 | inteiro A;
+  |         - declarada pela primeira vez aqui
+4 | inteiro A;
+  |         ^
+  |
+  = ajuda: remova a declaração repetida
+"
+    );
+}
+
+#[test]
+fn a_label_left_of_the_caret_hangs_under_its_span() {
+    let source = "inicio
+varinicio
+inteiro A, A;
+varfim;
+fim
+";
+
+    assert_eq!(
+        first(source),
+        "erro[EM2]: a variável 'A' já foi declarada
+ --> teste.mgol:3:12
+  |
+3 | inteiro A, A;
+  |         -  ^
+  |         |
+  |         declarada pela primeira vez aqui
+  |
+  = ajuda: remova a declaração repetida
+"
+    );
+}
+
+#[test]
+fn a_label_right_of_the_caret_follows_the_underlines() {
+    let source = "leia A; leia B;";
+    let diagnostic = Diagnostic::error(
+        Code::ES0,
+        span(source, (1, 1), (1, 5)),
+        vec![String::from("leia")],
+    )
+    .with_label(span(source, (1, 9), (1, 13)), Message::from(Text::Skipped));
+
+    assert_eq!(
+        Renderer::new("teste.mgol", source).render(&diagnostic),
+        "erro[ES0]: token inesperado 'leia'
+ --> teste.mgol:1:1
+  |
+1 | leia A; leia B;
+  | ^^^^    ---- ignorado ao recuperar do erro
+"
+    );
+}
+
+#[test]
+fn spans_over_several_lines_are_underlined_to_the_end_of_each_line() {
+    let source = "inicio
+escreva A;
+leia B;
+fim
+";
+    let diagnostic = Diagnostic::error(
+        Code::ES0,
+        span(source, (2, 9), (3, 5)),
+        vec![String::from("A")],
+    )
+    .with_label(span(source, (3, 1), (4, 4)), Message::from(Text::Skipped));
+
+    assert_eq!(
+        Renderer::new("teste.mgol", source).render(&diagnostic),
+        "erro[ES0]: token inesperado 'A'
+ --> teste.mgol:2:9
+  |
+2 | escreva A;
+  |         ^^
+3 | leia B;
+  | ------- ignorado ao recuperar do erro
+"
+    );
+}
+
+#[test]
+fn colour_is_only_used_when_asked_for() {
+    let source = "inicio @ fim";
+    let mut parser = Parser::new();
+    parser.parse(&mut Scanner::from_str(source));
+    let diagnostic = parser.diagnostics().iter().next().unwrap();
+
+    let plain = Renderer::new("teste.mgol", source).render(diagnostic);
+    let painted = Renderer::new("teste.mgol", source)
+        .with_color(true)
+        .render(diagnostic);
+    assert!(!plain.contains('\x1b'));
+    assert!(painted.contains("\x1b[1;31merro[EL0]\x1b[0m"));
+    assert!(painted.contains("\x1b[1;31m^\x1b[0m"));
+
+    let path = env::temp_dir().join(format!("mgol-render-{}.mgol", std::process::id()));
+    fs::write(&path, source).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_analisador_lexico"))
+        .args(["check", path.to_str().unwrap(), "--color", "never"])
+        .output()
+        .unwrap();
+    fs::remove_file(&path).ok();
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(!stdout.contains('\x1b'));
+    assert!(stdout.starts_with(&plain.replace("teste.mgol", path.to_str().unwrap())));
+}
@@ -0,0 +1,112 @@
+use analisador_lexico::{
+    catalog::Text,
+    diagnostic::{Code, Diagnostic},
+    parser::Parser,
+    scanner::Scanner,
+    span::Span,
+};
+
+/// The diagnostics of a program with `declarations` and `commands`.
+fn diagnostics(declarations: &str, commands: &str) -> Vec<Diagnostic> {
+    let source = format!(
+        "inicio\nvarinicio\n{}varfim;\n{}fim\n",
+        declarations, commands
+    );
+    let mut parser = Parser::new();
+    parser.parse(&mut Scanner::from_str(&source));
+
+    parser.diagnostics().iter().cloned().collect()
+}
+
+/// The only diagnostic of a program with `declarations` and `commands`.
+fn only(declarations: &str, commands: &str) -> Diagnostic {
+    let mut diagnostics = diagnostics(declarations, commands);
+    assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+
+    diagnostics.remove(0)
+}
+
+/// Line, column, end line and end column of `span`.
+fn position(span: &Span) -> (usize, usize, usize, usize) {
+    (span.line, span.column, span.end_line, span.end_column)
+}
+
+#[test]
+fn a_clean_program_has_no_diagnostics() {
+    let diagnostics = diagnostics(
+        "inteiro A;\nreal B;\nliteral C;\n",
+        "leia A;
+leia C;
+B <- A * 2.5;
+se (A <= B) entao
+escreva C;
+fimse
+repita (A < 10)
+A <- A + 1;
+fimrepita
+escreva \"fim\";
+",
+    );
+
+    assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+}
+
+#[test]
+fn undeclared_variables_are_em1() {
+    let diagnostic = only("inteiro A;\n", "leia B;\n");
+
+    assert_eq!(diagnostic.code, Code::EM1);
+    assert_eq!(diagnostic.args, vec!["B"]);
+    assert_eq!(position(&diagnostic.span), (5, 6, 5, 7));
+    assert_eq!(diagnostic.notes[0].text, Text::DeclareBetween);
+}
+
+#[test]
+fn redeclarations_are_em2_and_point_at_the_first_declaration() {
+    let diagnostic = only("inteiro A;\nreal A;\n", "");
+
+    assert_eq!(diagnostic.code, Code::EM2);
+    assert_eq!(diagnostic.args, vec!["A"]);
+    assert_eq!(position(&diagnostic.span), (4, 6, 4, 7));
+    assert_eq!(diagnostic.labels.len(), 1);
+    assert_eq!(diagnostic.labels[0].message.text, Text::FirstDeclaredHere);
+    assert_eq!(position(&diagnostic.labels[0].span), (3, 9, 3, 10));
+}
+
+#[test]
+fn assignments_of_another_type_are_em3() {
+    let diagnostic = only("inteiro A;\n", "A <- 1.5;\n");
+
+    assert_eq!(diagnostic.code, Code::EM3);
+    assert_eq!(diagnostic.args, vec!["real", "A", "inteiro"]);
+    assert_eq!(position(&diagnostic.span), (5, 6, 5, 9));
+    assert_eq!(diagnostic.labels[0].message.text, Text::DeclaredAs);
+    assert_eq!(position(&diagnostic.labels[0].span), (3, 9, 3, 10));
+}
+
+#[test]
+fn arithmetic_on_literals_is_em4() {
+    let diagnostic = only("literal A;\ninteiro B;\n", "B <- A + 1;\n");
+
+    assert_eq!(diagnostic.code, Code::EM4);
+    assert_eq!(diagnostic.args, vec!["+"]);
+    assert_eq!(position(&diagnostic.span), (6, 6, 6, 11));
+    assert_eq!(diagnostic.labels.len(), 1);
+    assert_eq!(diagnostic.labels[0].message.text, Text::HasTypeLiteral);
+    assert_eq!(position(&diagnostic.labels[0].span), (6, 6, 6, 7));
+}
+
+#[test]
+fn relations_with_literals_are_em5() {
+    let diagnostic = only(
+        "literal A;\ninteiro B;\n",
+        "se (B > A) entao\nescreva B;\nfimse\n",
+    );
+
+    assert_eq!(diagnostic.code, Code::EM5);
+    assert_eq!(diagnostic.args, vec![">", "inteiro", "literal"]);
+    assert_eq!(position(&diagnostic.span), (6, 5, 6, 10));
+    assert_eq!(diagnostic.labels.len(), 1);
+    assert_eq!(diagnostic.labels[0].message.text, Text::HasTypeLiteral);
+    assert_eq!(position(&diagnostic.labels[0].span), (6, 9, 6, 10));
+}
@@ -0,0 +1,133 @@
+use analisador_lexico::{
+    grammar::Grammar,
+    slr_table::{ActionTable, GotoTable, SLRAction},
+    table_builder::{build_tables, ConflictKind, ParseTables, TableMethod},
+};
+
+const METHODS: [TableMethod; 3] = [TableMethod::Slr, TableMethod::Lalr, TableMethod::Lr1];
+
+/// The kind and terminal of every conflict of `tables`.
+fn conflicts(tables: &ParseTables) -> Vec<(ConflictKind, &str)> {
+    tables
+        .conflicts
+        .iter()
+        .map(|conflict| (conflict.kind.clone(), conflict.terminal.as_str()))
+        .collect()
+}
+
+#[test]
+fn only_slr_conflicts_on_assignments_through_pointers() {
+    let grammar = Grammar::from_source(
+        "%token igual estrela id
+%start S
+S -> L igual R | R
+L -> estrela R | id
+R -> L
+",
+    )
+    .unwrap();
+
+    let slr = build_tables(&grammar, TableMethod::Slr);
+    assert_eq!(conflicts(&slr), vec![(ConflictKind::ShiftReduce, "igual")]);
+    assert!(build_tables(&grammar, TableMethod::Lalr)
+        .conflicts
+        .is_empty());
+    assert!(build_tables(&grammar, TableMethod::Lr1)
+        .conflicts
+        .is_empty());
+}
+
+#[test]
+fn only_lr1_tells_apart_reductions_merged_by_lalr() {
+    let grammar = Grammar::from_source(
+        "%token a b c d e
+%start S
+S -> a A d | b B d | a B e | b A e
+A -> c
+B -> c
+",
+    )
+    .unwrap();
+
+    for method in [TableMethod::Slr, TableMethod::Lalr] {
+        let tables = build_tables(&grammar, method);
+        assert_eq!(
+            conflicts(&tables),
+            vec![
+                (ConflictKind::ReduceReduce, "d"),
+                (ConflictKind::ReduceReduce, "e")
+            ],
+            "{}",
+            TableMethod::to_str(method)
+        );
+    }
+    assert!(build_tables(&grammar, TableMethod::Lr1)
+        .conflicts
+        .is_empty());
+}
+
+#[test]
+fn mgol_has_no_conflicts_with_any_method() {
+    let grammar = Grammar::new();
+
+    for (method, states) in METHODS.into_iter().zip([77, 77, 121]) {
+        let tables = build_tables(&grammar, method);
+        assert_eq!(tables.states, states, "{}", TableMethod::to_str(method));
+        assert!(
+            tables.conflicts.is_empty(),
+            "{}",
+            TableMethod::to_str(method)
+        );
+    }
+}
+
+/// The shipped tables were filled by hand before the builder existed. They
+/// agree on every shift, reduction and goto, but not on the recovery routine
+/// of 57 empty cells of states 48, 66, 67 and 68, which follow a `;`: the
+/// builder mostly picks ES4 there, where the CSV has ES0 or ES2.
+#[test]
+fn slr_tables_match_the_shipped_ones_but_for_error_routines() {
+    let grammar = Grammar::new();
+    let action_table =
+        ActionTable::from_reader(include_str!("../src/action_table.csv").as_bytes(), &grammar)
+            .unwrap();
+    let goto_table =
+        GotoTable::from_reader(include_str!("../src/goto_table.csv").as_bytes(), &grammar).unwrap();
+    let built = build_tables(&grammar, TableMethod::Slr);
+    assert_eq!(built.action_table.states(), action_table.states());
+
+    let mut routines: Vec<usize> = Vec::new();
+    for state in 0..action_table.states() {
+        for terminal in 0..grammar.terminals().len() {
+            match (
+                action_table.get(state, terminal),
+                built.action_table.get(state, terminal),
+            ) {
+                (SLRAction::E(shipped), SLRAction::E(generated)) if shipped != generated => {
+                    routines.push(state)
+                }
+                (shipped, generated) => assert_eq!(
+                    shipped,
+                    generated,
+                    "estado {}, terminal {}",
+                    state,
+                    grammar.terminals()[terminal]
+                ),
+            }
+        }
+
+        for non_terminal in 0..grammar.non_terminals().len() {
+            assert_eq!(
+                goto_table.try_get(state, non_terminal),
+                built.goto_table.try_get(state, non_terminal),
+                "estado {}, não terminal {}",
+                state,
+                grammar.non_terminals()[non_terminal]
+            );
+        }
+    }
+
+    assert_eq!(routines.len(), 57);
+    routines.dedup();
+    assert_eq!(routines, vec![48, 66, 67, 68]);
+}
@@ -0,0 +1,211 @@
+use std::io;
+
+use analisador_lexico::{
+    grammar::Grammar,
+    parser::Parser,
+    scanner::Scanner,
+    slr_table::{ActionTable, GotoTable, SLRAction},
+};
+
+const ACTION_TABLE: &str = include_str!("../src/action_table.csv");
+const GOTO_TABLE: &str = include_str!("../src/goto_table.csv");
+
+/// The shipped action table with the cell of state 0 on `inicio` replaced.
+fn action_table_with(cell: &str) -> io::Result<ActionTable> {
+    let csv = ACTION_TABLE.replacen("0,S2,", &format!("0,{},", cell), 1);
+    ActionTable::from_reader(csv.as_bytes(), &Grammar::new())
+}
+
+/// The shipped goto table with the cell of state 0 on `P` replaced.
+fn goto_table_with(cell: &str) -> io::Result<GotoTable> {
+    let csv = GOTO_TABLE.replacen("\n0,0,1,", &format!("\n0,0,{},", cell), 1);
+    assert!(csv != GOTO_TABLE);
+    GotoTable::from_reader(csv.as_bytes(), &Grammar::new())
+}
+
+#[test]
+fn the_shipped_tables_load_and_parse() {
+    let grammar = Grammar::new();
+    let action_table = ActionTable::from_reader(ACTION_TABLE.as_bytes(), &grammar).unwrap();
+    let goto_table = GotoTable::from_reader(GOTO_TABLE.as_bytes(), &grammar).unwrap();
+
+    let mut parser = Parser::with_tables(action_table, goto_table).unwrap();
+    let source = include_str!("../src/source_code.txt");
+    assert!(parser.parse(&mut Scanner::from_str(source)).is_some());
+}
+
+#[test]
+fn malformed_actions_are_invalid_data() {
+    for cell in ["XYZ", "S", "R", "E", "Sx", "S-1", "S+2", "Acc", "E999", ""] {
+        let err = action_table_with(cell).err();
+        assert_eq!(
+            err.map(|err| err.kind()),
+            Some(io::ErrorKind::InvalidData),
+            "{:?}",
+            cell
+        );
+    }
+
+    for cell in ["S3", "s2", "R3", "A", "E0", "E7"] {
+        assert!(action_table_with(cell).is_ok(), "{:?}", cell);
+    }
+}
+
+#[test]
+fn targets_outside_the_tables_are_invalid_data() {
+    let states = ActionTable::from_reader(ACTION_TABLE.as_bytes(), &Grammar::new())
+        .unwrap()
+        .states();
+    let rules = Grammar::new().rules().len();
+
+    for cell in [
+        format!("S{}", states),
+        String::from("S999"),
+        format!("R{}", rules),
+    ] {
+        let err = action_table_with(&cell).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{}", cell);
+    }
+    assert!(action_table_with(&format!("S{}", states - 1)).is_ok());
+    assert!(action_table_with(&format!("R{}", rules - 1)).is_ok());
+
+    for cell in ["999", "x", "-1"] {
+        let err = goto_table_with(cell).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{}", cell);
+    }
+    assert!(goto_table_with("0").is_ok());
+}
+
+#[test]
+fn lines_that_are_not_utf8_are_invalid_data() {
+    let (header, rows) = ACTION_TABLE.split_once('\n').unwrap();
+    let mut csv: Vec<u8> = format!("{}\n", header).into_bytes();
+    csv.extend(b"\xff\xfe,S2\n");
+    csv.extend(rows.as_bytes());
+
+    let err = ActionTable::from_reader(&csv[..], &Grammar::new())
+        .err()
+        .unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    assert!(err.to_string().contains("linha 2"), "{}", err);
+}
+
+/// The error `table` gives, which must be `InvalidData`.
+fn invalid_data<T>(table: io::Result<T>) -> String {
+    let err = table.err().unwrap();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{}", err);
+
+    err.to_string()
+}
+
+#[test]
+fn missing_or_repeated_rows_are_invalid_data() {
+    let grammar = Grammar::new();
+
+    let missing: Vec<&str> = ACTION_TABLE
+        .lines()
+        .filter(|line| !line.starts_with("5,"))
+        .collect();
+    let missing = missing.join("\n");
+    let err = invalid_data(ActionTable::from_reader(missing.as_bytes(), &grammar));
+    assert!(err.contains("estado 5"), "{}", err);
+
+    let row = ACTION_TABLE.lines().nth(3).unwrap();
+    let repeated = format!("{}\n{}\n", ACTION_TABLE.trim_end(), row);
+    let err = invalid_data(ActionTable::from_reader(repeated.as_bytes(), &grammar));
+    let lines = ACTION_TABLE.lines().count();
+    assert!(err.contains(&format!("linha {}", lines + 1)), "{}", err);
+    assert!(
+        err.contains("estado 2") && err.contains("linha 4"),
+        "{}",
+        err
+    );
+
+    let row = GOTO_TABLE.lines().nth(1).unwrap();
+    let repeated = format!("{}\n{}\n", GOTO_TABLE.trim_end(), row);
+    let err = invalid_data(GotoTable::from_reader(repeated.as_bytes(), &grammar));
+    assert!(err.contains("estado 0"), "{}", err);
+}
+
+#[test]
+fn missing_or_repeated_columns_are_invalid_data() {
+    let grammar = Grammar::new();
+
+    // Every row loses its last cell along with the `$` column.
+    let missing: Vec<&str> = ACTION_TABLE
+        .lines()
+        .map(|line| &line[..line.rfind(',').unwrap()])
+        .collect();
+    let err = invalid_data(ActionTable::from_reader(
+        missing.join("\n").as_bytes(),
+        &grammar,
+    ));
+    assert!(err.contains("'eof'"), "{}", err);
+
+    let repeated = ACTION_TABLE.replacen("state,inicio,varinicio,", "state,inicio,inicio,", 1);
+    assert!(repeated != ACTION_TABLE);
+    let err = invalid_data(ActionTable::from_reader(repeated.as_bytes(), &grammar));
+    assert!(
+        err.contains("colunas 2 e 3") && err.contains("'inicio'"),
+        "{}",
+        err
+    );
+
+    let (header, rows) = GOTO_TABLE.split_once('\n').unwrap();
+    let columns: Vec<&str> = header.split(',').collect();
+    let repeated = format!(
+        "{},{}\n{}",
+        columns[..columns.len() - 1].join(","),
+        columns[1],
+        rows
+    );
+    let err = invalid_data(GotoTable::from_reader(repeated.as_bytes(), &grammar));
+    assert!(err.contains(&format!("'{}'", columns[1])), "{}", err);
+}
+
+#[test]
+fn tables_that_do_not_fit_the_grammar_are_rejected() {
+    let grammar = Grammar::new();
+    let terminals = grammar.terminals().len();
+    let non_terminals = grammar.non_terminals().len();
+    let goto_table = || GotoTable::from_reader(GOTO_TABLE.as_bytes(), &grammar).unwrap();
+    let states = goto_table().states();
+
+    let err = invalid_data(Parser::with_tables(
+        ActionTable::new(states, terminals - 1),
+        goto_table(),
+    ));
+    assert!(err.contains("terminais"), "{}", err);
+
+    let err = invalid_data(Parser::with_tables(
+        ActionTable::new(states, terminals),
+        GotoTable::new(states, non_terminals + 1),
+    ));
+    assert!(err.contains("não-terminais"), "{}", err);
+
+    let err = invalid_data(Parser::with_tables(
+        ActionTable::new(states - 1, terminals),
+        goto_table(),
+    ));
+    assert!(err.contains("estados"), "{}", err);
+
+    let mut action_table = ActionTable::new(states, terminals);
+    action_table.insert(0, 0, SLRAction::S(states));
+    let err = invalid_data(Parser::with_tables(action_table, goto_table()));
+    assert!(err.contains(&format!("estado {}", states)), "{}", err);
+
+    let mut action_table = ActionTable::new(states, terminals);
+    action_table.insert(0, 0, SLRAction::R(grammar.rules().len()));
+    assert!(invalid_data(Parser::with_tables(action_table, goto_table())).contains("regra"));
+
+    let mut goto_table = GotoTable::new(states, non_terminals);
+    goto_table.insert(0, 0, states);
+    let action_table = ActionTable::new(states, terminals);
+    assert!(invalid_data(Parser::with_tables(action_table, goto_table)).contains("desvia"));
+
+    assert!(Parser::with_tables(
+        ActionTable::new(states, terminals),
+        GotoTable::new(states, non_terminals)
+    )
+    .is_ok());
+}
@@ -0,0 +1,91 @@
+use analisador_lexico::{
+    parser::Parser,
+    scanner::Scanner,
+    trace::{self, TraceFormat},
+};
+
+/// The trace of `source` written in `format`.
+fn trace(source: &str, format: TraceFormat) -> String {
+    let mut parser = Parser::new().with_trace(true);
+    parser.parse(&mut Scanner::from_str(source));
+
+    let mut out: Vec<u8> = Vec::new();
+    trace::write_trace(parser.trace(), format, &mut out).unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn a_program_is_traced_step_by_step() {
+    let source = "inicio
+varinicio
+varfim;
+leia A;
+fim
+";
+
+    assert_eq!(
+        trace(source, TraceFormat::Csv),
+        "passo,pilha,entrada,acao
+1,0,inicio,S2
+2,0 inicio 2,varinicio,S4
+3,0 inicio 2 varinicio 4,varfim,S20
+4,0 inicio 2 varinicio 4 varfim 20,; (pt_v),S48
+5,0 inicio 2 varinicio 4 varfim 20 pt_v 48,leia,R4 (LV -> varfim pt_v)
+6,0 inicio 2 varinicio 4 LV 18,leia,R2 (V -> varinicio LV)
+7,0 inicio 2 V 3,leia,S11
+8,0 inicio 2 V 3 leia 11,A (id),S29
+9,0 inicio 2 V 3 leia 11 id 29,; (pt_v),S51
+10,0 inicio 2 V 3 leia 11 id 29 pt_v 51,fim,R12 (ES -> leia id pt_v)
+11,0 inicio 2 V 3 ES 6,fim,S10
+12,0 inicio 2 V 3 ES 6 fim 10,EOF (eof),R38 (A -> fim)
+13,0 inicio 2 V 3 ES 6 A 25,EOF (eof),R11 (A -> ES A)
+14,0 inicio 2 V 3 A 5,EOF (eof),R1 (P -> inicio V A)
+15,0 P 1,EOF (eof),A
+"
+    );
+}
+
+#[test]
+fn errors_and_the_repaired_input_are_traced() {
+    let source = "inicio
+varinicio
+varfim;
+leia A
+fim
+";
+
+    assert_eq!(
+        trace(source, TraceFormat::Table),
+        "Passo  Pilha                                     Entrada    Ação
+    1  0                                         inicio     S2
+    2  0 inicio 2                                varinicio  S4
+    3  0 inicio 2 varinicio 4                    varfim     S20
+    4  0 inicio 2 varinicio 4 varfim 20          ; (pt_v)   S48
+    5  0 inicio 2 varinicio 4 varfim 20 pt_v 48  leia       R4 (LV -> varfim pt_v)
+    6  0 inicio 2 varinicio 4 LV 18              leia       R2 (V -> varinicio LV)
+    7  0 inicio 2 V 3                            leia       S11
+    8  0 inicio 2 V 3 leia 11                    A (id)     S29
+    9  0 inicio 2 V 3 leia 11 id 29              fim        E2
+   10  0 inicio 2 V 3 leia 11 id 29              ; (pt_v)   S51
+   11  0 inicio 2 V 3 leia 11 id 29 pt_v 51      fim        R12 (ES -> leia id pt_v)
+   12  0 inicio 2 V 3 ES 6                       fim        S10
+   13  0 inicio 2 V 3 ES 6 fim 10                EOF (eof)  R38 (A -> fim)
+   14  0 inicio 2 V 3 ES 6 A 25                  EOF (eof)  R11 (A -> ES A)
+   15  0 inicio 2 V 3 A 5                        EOF (eof)  R1 (P -> inicio V A)
+   16  0 P 1                                     EOF (eof)  A
+"
+    );
+
+    let html = trace(source, TraceFormat::Html);
+    assert!(html.starts_with("<!DOCTYPE html>\n"), "{}", html);
+    assert!(html.ends_with("</table>\n</body>\n</html>\n"), "{}", html);
+    assert!(html.contains(
+        "<tr class=\"erro\"><td>9</td><td>0 inicio 2 V 3 leia 11 id 29</td><td>fim</td><td>E2</td></tr>\n"
+    ));
+    assert!(
+        html.contains("<td>R12 (ES -&gt; leia id pt_v)</td>"),
+        "{}",
+        html
+    );
+    assert_eq!(html.matches("<tr class=\"erro\">").count(), 1);
+}
@@ -0,0 +1,93 @@
+use analisador_lexico::{parse_tree::ParseTree, parser::Parser, scanner::Scanner};
+
+const SOURCE: &str = "inicio
+varinicio
+varfim;
+leia A;
+fim
+";
+
+fn tree(source: &str) -> ParseTree {
+    let mut parser = Parser::new().with_tree(true);
+    parser.parse(&mut Scanner::from_str(source));
+
+    parser.tree().cloned().unwrap()
+}
+
+#[test]
+fn the_tree_is_exported_as_text() {
+    assert_eq!(
+        tree(SOURCE).to_text(),
+        "P
+├── inicio
+├── V
+│   ├── varinicio
+│   └── LV
+│       ├── varfim
+│       └── pt_v ;
+└── A
+    ├── ES
+    │   ├── leia
+    │   ├── id A
+    │   └── pt_v ;
+    └── A
+        └── fim
+"
+    );
+}
+
+#[test]
+fn the_tree_is_exported_as_dot() {
+    assert_eq!(
+        tree(SOURCE).to_dot(),
+        "digraph arvore {
+    ordering=out;
+    n0 [label=\"P\", shape=ellipse];
+    n1 [label=\"inicio\", shape=box];
+    n0 -> n1;
+    n2 [label=\"V\", shape=ellipse];
+    n3 [label=\"varinicio\", shape=box];
+    n2 -> n3;
+    n4 [label=\"LV\", shape=ellipse];
+    n5 [label=\"varfim\", shape=box];
+    n4 -> n5;
+    n6 [label=\"pt_v ;\", shape=box];
+    n4 -> n6;
+    n2 -> n4;
+    n0 -> n2;
+    n7 [label=\"A\", shape=ellipse];
+    n8 [label=\"ES\", shape=ellipse];
+    n9 [label=\"leia\", shape=box];
+    n8 -> n9;
+    n10 [label=\"id A\", shape=box];
+    n8 -> n10;
+    n11 [label=\"pt_v ;\", shape=box];
+    n8 -> n11;
+    n7 -> n8;
+    n12 [label=\"A\", shape=ellipse];
+    n13 [label=\"fim\", shape=box];
+    n12 -> n13;
+    n7 -> n12;
+    n0 -> n7;
+}
+"
+    );
+}
+
+/// Quotes and backslashes of a literal are escaped in its label.
+#[test]
+fn labels_are_escaped_in_dot() {
+    let source = "inicio
+varinicio
+varfim;
+escreva \"a\\nb\";
+fim
+";
+    let dot = tree(source).to_dot();
+
+    assert!(
+        dot.contains("[label=\"lit \\\"a\\\\nb\\\"\", shape=box];"),
+        "{}",
+        dot
+    );
+}